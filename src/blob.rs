@@ -0,0 +1,181 @@
+//! Content-addressed golden files
+//!
+//! The content is saved to a blob named by its hash (e.g. `blobs/3a7b….json`),
+//! and the golden file is a pointer holding the hash,
+//! so identical responses across tests share a single blob.
+
+use crate::GoldrustError;
+use sha2::{Digest, Sha256};
+use std::cmp::Ordering;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// The directory of the blobs, next to the golden files
+pub(crate) const DIR: &str = "blobs";
+
+/// A pluggable hash for naming the blobs of content-addressed golden files
+///
+/// Implement this to use a hash other than SHA-256, such as BLAKE3 or a FIPS approved hash,
+/// and set it with [`Goldrust::with_hasher`](crate::Goldrust::with_hasher).
+/// The digest names the blob file, so it must only contain ASCII letters, digits, `-` and `_`.
+/// Changing the hash changes the blob names, so re-record the golden files after changing it.
+pub trait GoldenHasher: std::fmt::Debug + Send + Sync {
+    /// The digest of the serialized content, e.g. hex-encoded
+    fn digest(&self, bytes: &[u8]) -> String;
+}
+
+/// The default hash, the hex-encoded SHA-256 digest
+#[derive(Clone, Copy, Eq, PartialEq, Debug, Default)]
+pub struct Sha256Hasher;
+
+impl GoldenHasher for Sha256Hasher {
+    fn digest(&self, bytes: &[u8]) -> String {
+        Sha256::digest(bytes)
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect()
+    }
+}
+
+/// A shared [`GoldenHasher`], which defaults to [`Sha256Hasher`]
+///
+/// Hashers are compared by identity,
+/// so that [`Goldrust`](crate::Goldrust) keeps implementing the common traits.
+#[derive(Clone, Debug)]
+pub(crate) struct Hasher(pub(crate) Arc<dyn GoldenHasher>);
+
+impl Hasher {
+    fn address(&self) -> *const () {
+        Arc::as_ptr(&self.0) as *const ()
+    }
+}
+
+impl Default for Hasher {
+    fn default() -> Self {
+        Self(Arc::new(Sha256Hasher))
+    }
+}
+
+impl PartialEq for Hasher {
+    fn eq(&self, other: &Self) -> bool {
+        self.address() == other.address()
+    }
+}
+
+impl Eq for Hasher {}
+
+impl PartialOrd for Hasher {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Hasher {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.address().cmp(&other.address())
+    }
+}
+
+/// Whether the digest can be used as a blob file name
+fn is_valid_digest(digest: &str) -> bool {
+    !digest.is_empty()
+        && digest
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+/// The path of the blob with the hash, for the golden file at `pointer_path`
+fn blob_path(pointer_path: &Path, hash: &str, extension: &str) -> PathBuf {
+    pointer_path
+        .with_file_name(DIR)
+        .join(format!("{}.{}", hash, extension))
+}
+
+/// Write the bytes to their blob, and the hash to the pointer file
+///
+/// Blobs are only written when they don't exist yet, as the same hash means the same content.
+pub(crate) fn write(
+    pointer_path: &Path,
+    bytes: &[u8],
+    extension: &str,
+    compress: bool,
+    hasher: &Hasher,
+) -> Result<(), GoldrustError> {
+    let hash = hasher.0.digest(bytes);
+    if !is_valid_digest(&hash) {
+        return Err(GoldrustError::InvalidConfig {
+            reason: format!("The digest can't be used as a blob file name: {:?}", hash),
+        });
+    }
+    let blob_path = blob_path(pointer_path, &hash, extension);
+    if !blob_path.exists() {
+        if let Some(dir) = blob_path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        crate::write_golden_file(&blob_path, bytes, compress)?;
+    }
+    crate::write_golden_file(pointer_path, format!("{}\n", hash).as_bytes(), false)
+}
+
+/// Resolve the pointer file into the path of its blob
+pub(crate) fn resolve(pointer_path: &Path, extension: &str) -> Result<PathBuf, GoldrustError> {
+    let pointer = std::fs::read_to_string(pointer_path)
+        .inspect_err(|_e| tracing::error!(?pointer_path, "Error reading file"))?;
+    let hash = pointer.trim();
+    if !is_valid_digest(hash) {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            format!("Invalid blob pointer in {}", pointer_path.display()),
+        )
+        .into());
+    }
+    Ok(blob_path(pointer_path, hash, extension))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn identical_content_shares_a_blob() {
+        let temp = crate::test_support::temp_dir();
+        let dir = temp.path();
+        let first = dir.join("first.json");
+        let second = dir.join("second.json");
+        write(&first, b"{}", "json", false, &Hasher::default()).unwrap();
+        write(&second, b"{}", "json", false, &Hasher::default()).unwrap();
+
+        let blob = resolve(&first, "json").unwrap();
+        assert_eq!(blob, resolve(&second, "json").unwrap());
+        assert_eq!(blob.parent().unwrap(), dir.join(DIR));
+        assert_eq!(std::fs::read(blob).unwrap(), b"{}");
+    }
+
+    #[test]
+    fn custom_hasher_names_the_blob() {
+        #[derive(Debug)]
+        struct LengthHasher;
+
+        impl GoldenHasher for LengthHasher {
+            fn digest(&self, bytes: &[u8]) -> String {
+                format!("len-{}", bytes.len())
+            }
+        }
+
+        let temp = crate::test_support::temp_dir();
+        let dir = temp.path();
+        let pointer = dir.join("golden.json");
+        write(
+            &pointer,
+            b"{}",
+            "json",
+            false,
+            &Hasher(Arc::new(LengthHasher)),
+        )
+        .unwrap();
+        assert_eq!(
+            resolve(&pointer, "json").unwrap(),
+            dir.join(DIR).join("len-2.json")
+        );
+    }
+}
@@ -0,0 +1,128 @@
+use crate::GoldrustError;
+use base64::Engine;
+use serde_json::Value;
+
+/// The encoding of bodies which are not valid UTF-8
+const BASE64: &str = "base64";
+
+/// Convert a raw body into a JSON value
+///
+/// Empty bodies (e.g. of a `204` response) are `null`,
+/// UTF-8 bodies are kept as a string,
+/// while other bodies are wrapped as `{"encoding": "base64", "data": "..."}`.
+pub(crate) fn from_bytes(bytes: &[u8]) -> Value {
+    if bytes.is_empty() {
+        return Value::Null;
+    }
+    match std::str::from_utf8(bytes) {
+        Ok(text) => Value::String(text.to_string()),
+        Err(_) => to_base64(bytes),
+    }
+}
+
+/// Convert a raw response body into a JSON value, given its `Content-Type`
+///
+/// Objects and arrays are kept as JSON, as are other JSON values of a JSON content type.
+/// Everything else is converted with [`from_bytes`], so that a text body such as `123`
+/// stays text, and a JSON string body such as `"abc"` keeps its quotes on replay.
+#[cfg(feature = "reqwest")]
+pub(crate) fn from_response_bytes(bytes: &[u8], content_type: Option<&str>) -> Value {
+    let is_json = content_type.and_then(crate::GoldenFormat::from_content_type)
+        == Some(crate::GoldenFormat::Json);
+    match serde_json::from_slice::<Value>(bytes) {
+        Ok(value @ (Value::Object(_) | Value::Array(_))) => value,
+        Ok(value) if is_json && !value.is_string() => value,
+        _ => from_bytes(bytes),
+    }
+}
+
+/// Wrap a raw body as `{"encoding": "base64", "data": "..."}`, regardless of its content
+pub(crate) fn to_base64(bytes: &[u8]) -> Value {
+    serde_json::json!({
+        "encoding": BASE64,
+        "data": base64::engine::general_purpose::STANDARD.encode(bytes),
+    })
+}
+
+/// Convert a JSON value back into the raw body
+///
+/// Base64 wrappers are decoded, `null` is an empty body, strings are used as is,
+/// and other values are serialized as JSON.
+pub(crate) fn to_bytes(body: &Value) -> Result<Vec<u8>, GoldrustError> {
+    if let Some(data) = base64_data(body) {
+        return Ok(base64::engine::general_purpose::STANDARD.decode(data)?);
+    }
+    match body {
+        Value::Null => Ok(Vec::new()),
+        Value::String(text) => Ok(text.clone().into_bytes()),
+        body => Ok(serde_json::to_vec(body)?),
+    }
+}
+
+/// Whether the value is an empty body, which is `null` or an empty string
+pub(crate) fn is_empty(body: &Value) -> bool {
+    match body {
+        Value::Null => true,
+        Value::String(text) => text.is_empty(),
+        _ => false,
+    }
+}
+
+/// The data of a base64 wrapper, if the value is one
+fn base64_data(body: &Value) -> Option<&str> {
+    let map = body.as_object()?;
+    if map.len() != 2 || map.get("encoding")?.as_str()? != BASE64 {
+        return None;
+    }
+    map.get("data")?.as_str()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        for bytes in [b"<html>".as_slice(), &[0xff, 0x00]] {
+            assert_eq!(to_bytes(&from_bytes(bytes)).unwrap(), bytes);
+        }
+        assert_eq!(
+            from_bytes(&[0xff, 0x00]),
+            serde_json::json!({"encoding": "base64", "data": "/wA="})
+        );
+    }
+
+    #[test]
+    fn empty_body_is_null() {
+        assert_eq!(from_bytes(b""), Value::Null);
+        assert!(to_bytes(&Value::Null).unwrap().is_empty());
+        assert!(is_empty(&Value::String(String::new())));
+    }
+
+    #[cfg(feature = "reqwest")]
+    #[test]
+    fn response_bytes_keep_scalars_as_text() {
+        assert_eq!(
+            from_response_bytes(br#"{"a":1}"#, None),
+            serde_json::json!({"a": 1})
+        );
+        assert_eq!(from_response_bytes(b"123", Some("text/plain")), "123");
+        assert_eq!(from_response_bytes(b"true", None), "true");
+        assert_eq!(
+            from_response_bytes(b"123", Some("application/json")),
+            serde_json::json!(123)
+        );
+        for content_type in [None, Some("application/json")] {
+            let body = from_response_bytes(br#""abc""#, content_type);
+            assert_eq!(to_bytes(&body).unwrap(), br#""abc""#);
+        }
+    }
+
+    #[test]
+    fn to_bytes_serializes_json() {
+        assert_eq!(
+            to_bytes(&serde_json::json!({"a": 1})).unwrap(),
+            br#"{"a":1}"#
+        );
+    }
+}
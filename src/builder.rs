@@ -0,0 +1,803 @@
+use crate::codec::Codec;
+use crate::GoldenCodec;
+use crate::{
+    existing_named_golden_files, golden_file_extension, lookup_path, named_golden_file_path,
+    response_source, GoldenFormat, GoldenPath, Goldrust, GoldrustConfig, GoldrustError,
+};
+use std::collections::HashMap;
+use std::path::{Component, Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock, PoisonError};
+
+/// A builder for explicitly configuring [`Goldrust`]
+///
+/// Configurations which are not set fall back to the environment variables,
+/// the same way as [`Goldrust::new`]:
+/// - `dir`: `GOLDRUST_DIR`, resolved against `GOLDRUST_WORKSPACE_ROOT` or `CARGO_MANIFEST_DIR` when relative
+/// - `allow_external_api_call`: `GOLDRUST_ALLOW_EXTERNAL_API_CALL`
+/// - `update_golden_files`: `GOLDRUST_UPDATE_GOLDEN_FILES`
+/// - `review`: `GOLDRUST_REVIEW`
+/// - `forbid_update`: `GOLDRUST_FORBID_UPDATE`
+/// - `create_missing`: `GOLDRUST_CREATE_MISSING`
+/// - `profile`: `GOLDRUST_PROFILE`
+/// - `name_prefix_strip`: `GOLDRUST_NAME_PREFIX_STRIP`
+///
+/// The golden file name has no fallback and must always be set.
+#[derive(Clone, Debug, Default)]
+pub struct GoldrustBuilder {
+    dir: Option<PathBuf>,
+    fallback_dirs: Vec<PathBuf>,
+    allow_external_api_call: Option<bool>,
+    update_golden_files: Option<bool>,
+    golden_file_name: Option<String>,
+    named_golden_files: Vec<String>,
+    format: GoldenFormat,
+    codec: Option<Codec>,
+    compress: bool,
+    review: Option<bool>,
+    forbid_update: Option<bool>,
+    create_missing: Option<bool>,
+    create_dirs: Option<bool>,
+    profile: Option<String>,
+    extension: Option<String>,
+    append_extension: Option<bool>,
+    content_addressed: bool,
+    name_prefix_strip: Option<String>,
+}
+
+impl GoldrustBuilder {
+    /// The directory where the golden files will be saved
+    pub fn dir(mut self, dir: PathBuf) -> Self {
+        self.dir = Some(dir);
+        self
+    }
+
+    /// The ordered directories of the golden files, for layering local overrides over a shared set
+    ///
+    /// Golden files are read from the first directory which has them,
+    /// and always written to the first directory, which must be writable.
+    /// The golden files exist for [`ResponseSource`](crate::ResponseSource) when any directory has them.
+    /// An empty list keeps the configured directory.
+    pub fn dirs(mut self, dirs: Vec<PathBuf>) -> Self {
+        let mut dirs = dirs.into_iter();
+        if let Some(dir) = dirs.next() {
+            self.dir = Some(dir);
+            self.fallback_dirs = dirs.collect();
+        }
+        self
+    }
+
+    /// Whether external api calls are allowed
+    pub fn allow_external_api_call(mut self, allow_external_api_call: bool) -> Self {
+        self.allow_external_api_call = Some(allow_external_api_call);
+        self
+    }
+
+    /// Whether golden files should be updated
+    pub fn update_golden_files(mut self, update_golden_files: bool) -> Self {
+        self.update_golden_files = Some(update_golden_files);
+        self
+    }
+
+    /// The name of the golden file, without the extension
+    ///
+    /// The extension is appended based on the format.
+    pub fn golden_file_name(mut self, golden_file_name: String) -> Self {
+        self.golden_file_name = Some(golden_file_name);
+        self
+    }
+
+    /// Register named golden files, which are required in addition to the golden file name
+    ///
+    /// When named golden files are registered,
+    /// the existence check for local responses is done on all of them,
+    /// instead of the single golden file.
+    /// Check [`Goldrust::save_named`] for details.
+    pub fn named_golden_files(mut self, names: Vec<String>) -> Self {
+        self.named_golden_files = names;
+        self
+    }
+
+    /// The format the golden file is saved in
+    pub fn format(mut self, format: GoldenFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// The codec used to serialize golden files, which overrides the format
+    ///
+    /// The extension of the golden file follows the codec.
+    pub fn codec(mut self, codec: impl GoldenCodec + 'static) -> Self {
+        self.codec = Some(Codec(Arc::new(codec)));
+        self
+    }
+
+    /// The extension of the golden files, without the leading dot (e.g. `golden.json`)
+    ///
+    /// Defaults to the extension of the format, or the codec when set.
+    pub fn extension(mut self, extension: String) -> Self {
+        self.extension = Some(extension);
+        self
+    }
+
+    /// Whether the extension is appended to the golden file name
+    ///
+    /// When not set, the extension is appended unless the name already ends with it
+    /// (e.g. `foo.json` stays `foo.json` instead of `foo.json.json`).
+    /// When `false`, the name is used verbatim, and its own extension (e.g. `foo.xml`)
+    /// becomes the extension of the golden files.
+    pub fn append_extension(mut self, append_extension: bool) -> Self {
+        self.append_extension = Some(append_extension);
+        self
+    }
+
+    /// Whether golden files are compressed with gzip, which defaults to `false`
+    ///
+    /// Compressed golden files get an additional `.gz` extension (e.g. `.json.gz`),
+    /// and are decompressed transparently when read through Goldrust.
+    pub fn compress(mut self, compress: bool) -> Self {
+        self.compress = compress;
+        self
+    }
+
+    /// Whether golden files are stored by the hash of their content, which defaults to `false`
+    ///
+    /// Saving writes the content to a blob in the `blobs` directory next to the golden files,
+    /// named by its hash (e.g. `blobs/3a7b….json`),
+    /// which is SHA-256 unless set with [`Goldrust::with_hasher`],
+    /// and the golden file itself holds the hash, pointing to the blob.
+    /// Reading resolves the pointer transparently.
+    /// Identical responses across tests share a single blob,
+    /// which keeps the golden directory small.
+    pub fn content_addressed(mut self, content_addressed: bool) -> Self {
+        self.content_addressed = content_addressed;
+        self
+    }
+
+    /// Whether recording writes to pending golden files for review
+    ///
+    /// When reviewing, saving writes to a pending golden file (e.g. `test-name.json.new`)
+    /// instead of overwriting the golden file.
+    /// Pending golden files can then be accepted or rejected with [`review::review`](crate::review::review).
+    ///
+    /// Falls back to `GOLDRUST_REVIEW`, which defaults to `false`.
+    pub fn review(mut self, review: bool) -> Self {
+        self.review = Some(review);
+        self
+    }
+
+    /// Whether writing golden files is forbidden
+    ///
+    /// When forbidden, saving returns [`GoldrustError::UpdateForbidden`]
+    /// instead of writing the golden file,
+    /// so CI fails safe even with updates accidentally enabled.
+    ///
+    /// Falls back to `GOLDRUST_FORBID_UPDATE`, which defaults to `false`.
+    pub fn forbid_update(mut self, forbid_update: bool) -> Self {
+        self.forbid_update = Some(forbid_update);
+        self
+    }
+
+    /// Whether missing golden files are created, without updating existing ones
+    ///
+    /// When a golden file does not exist and external api calls are allowed,
+    /// the response is recorded from the external api and saved.
+    /// Existing golden files are used as is, and never re-recorded.
+    ///
+    /// Falls back to `GOLDRUST_CREATE_MISSING`, which defaults to `false`.
+    pub fn create_missing(mut self, create_missing: bool) -> Self {
+        self.create_missing = Some(create_missing);
+        self
+    }
+
+    /// Whether the golden directory is created when saving, which defaults to `true`
+    ///
+    /// Saving creates the missing directories of the golden file with `create_dir_all`,
+    /// so the first recording doesn't fail when the golden directory doesn't exist yet.
+    /// With `false`, saving returns [`GoldrustError::Io`] instead.
+    pub fn create_dirs(mut self, create_dirs: bool) -> Self {
+        self.create_dirs = Some(create_dirs);
+        self
+    }
+
+    /// The profile, which keeps a distinct set of golden files in a subdirectory
+    ///
+    /// e.g. with the `staging` profile,
+    /// golden files are saved in `tests/resources/golden/staging/test-name.json`.
+    ///
+    /// Falls back to `GOLDRUST_PROFILE`, which defaults to no profile.
+    pub fn profile(mut self, profile: String) -> Self {
+        self.profile = Some(profile);
+        self
+    }
+
+    /// A prefix which is stripped from the golden file name, for shorter file names
+    ///
+    /// Common module path segments of derived names can be removed,
+    /// e.g. `my_crate-api` turns `my_crate-api-users-get_user` into `users-get_user`.
+    /// The `-` separator following the prefix is stripped as well.
+    /// Names which don't start with the prefix are kept as is.
+    ///
+    /// While a prefix is set, building returns [`GoldrustError::InvalidConfig`]
+    /// when two different names end up with the same golden file after stripping.
+    ///
+    /// Falls back to `GOLDRUST_NAME_PREFIX_STRIP`, which defaults to no prefix.
+    pub fn name_prefix_strip(mut self, prefix: String) -> Self {
+        self.name_prefix_strip = Some(prefix);
+        self
+    }
+
+    /// Build the Goldrust instance
+    ///
+    /// Returns an error when an environment variable is not parseable,
+    /// or when the configuration is an invalid combination.
+    #[tracing::instrument]
+    pub fn build(self) -> Result<Goldrust, GoldrustError> {
+        let golden_file_name = self.golden_file_name.ok_or(GoldrustError::InvalidConfig {
+            reason: "The golden file name must be set".to_string(),
+        })?;
+        let name_prefix_strip = self
+            .name_prefix_strip
+            .or_else(|| std::env::var("GOLDRUST_NAME_PREFIX_STRIP").ok())
+            .filter(|prefix| !prefix.is_empty());
+        let original_name = golden_file_name.clone();
+        let golden_file_name = match &name_prefix_strip {
+            Some(prefix) => strip_name_prefix(golden_file_name, prefix),
+            None => golden_file_name,
+        };
+
+        let golden_file_dir = match self.dir {
+            Some(dir) => dir,
+            None => env_dir(),
+        };
+        let profile = self
+            .profile
+            .or_else(|| std::env::var("GOLDRUST_PROFILE").ok())
+            .filter(|profile| !profile.is_empty());
+        let dir = golden_file_dir;
+        let with_profile = |dir: &PathBuf| match &profile {
+            Some(profile) => dir.join(profile),
+            None => dir.clone(),
+        };
+        let golden_file_dir = with_profile(&dir);
+        let fallback_dirs: Vec<PathBuf> = self.fallback_dirs.iter().map(with_profile).collect();
+        let extension = match (&self.extension, &self.codec) {
+            (Some(extension), _) => extension.as_str(),
+            (None, Some(codec)) => codec.0.extension(),
+            (None, None) => self.format.extension(),
+        };
+        let extension = golden_file_extension(extension, self.compress);
+        let (golden_file_name, extension) = match self.append_extension {
+            Some(true) => (golden_file_name, extension),
+            Some(false) => match golden_file_name.rsplit_once('.') {
+                Some((name, extension)) if !name.is_empty() && !extension.is_empty() => {
+                    (name.to_string(), extension.to_string())
+                }
+                _ => {
+                    return Err(GoldrustError::InvalidConfig {
+                        reason: format!(
+                        "The golden file name must have an extension when it is not appended: {}",
+                        golden_file_name
+                    ),
+                    })
+                }
+            },
+            None => match golden_file_name.strip_suffix(&format!(".{}", extension)) {
+                Some(name) if !name.is_empty() => (name.to_string(), extension),
+                _ => (golden_file_name, extension),
+            },
+        };
+        let golden_file_path = golden_file_dir.join(format!("{}.{}", golden_file_name, extension));
+        if name_prefix_strip.is_some() {
+            register_stripped_name(&golden_file_path, &original_name)?;
+        }
+
+        let allow_external_api_call = match self.allow_external_api_call {
+            Some(allow_external_api_call) => allow_external_api_call,
+            None => env_bool("GOLDRUST_ALLOW_EXTERNAL_API_CALL")?,
+        };
+
+        let update_golden_files = match self.update_golden_files {
+            Some(update_golden_files) => update_golden_files,
+            None => env_bool("GOLDRUST_UPDATE_GOLDEN_FILES")?,
+        };
+
+        let review = match self.review {
+            Some(review) => review,
+            None => env_bool("GOLDRUST_REVIEW")?,
+        };
+
+        let forbid_update = match self.forbid_update {
+            Some(forbid_update) => forbid_update,
+            None => env_bool("GOLDRUST_FORBID_UPDATE")?,
+        };
+
+        let create_missing = match self.create_missing {
+            Some(create_missing) => create_missing,
+            None => env_bool("GOLDRUST_CREATE_MISSING")?,
+        };
+
+        let golden_file_paths = if self.named_golden_files.is_empty() {
+            // Tests which only save named golden files don't have the golden file itself
+            let existing_named = existing_named_golden_files(
+                &golden_file_path,
+                &golden_file_name,
+                &extension,
+                &golden_file_dir,
+                &fallback_dirs,
+            );
+            if lookup_path(&golden_file_path, &golden_file_dir, &fallback_dirs).exists()
+                || existing_named.is_empty()
+            {
+                vec![golden_file_path.clone()]
+            } else {
+                existing_named
+            }
+        } else {
+            self.named_golden_files
+                .iter()
+                .map(|name| {
+                    named_golden_file_path(&golden_file_path, &golden_file_name, name, &extension)
+                })
+                .collect()
+        };
+        // Creating missing golden files is an update of only those golden files
+        let update_golden_files = update_golden_files
+            || (create_missing
+                && allow_external_api_call
+                && golden_file_paths
+                    .iter()
+                    .any(|path| !lookup_path(path, &golden_file_dir, &fallback_dirs).exists()));
+        let save_check = !update_golden_files;
+
+        let golden_file_paths: Vec<PathBuf> = golden_file_paths
+            .iter()
+            .map(|path| lookup_path(path, &golden_file_dir, &fallback_dirs))
+            .collect();
+        let decision = response_source(
+            allow_external_api_call,
+            update_golden_files,
+            &golden_file_paths,
+        )?;
+
+        let named = self
+            .named_golden_files
+            .into_iter()
+            .map(|name| (name, save_check))
+            .collect();
+
+        Ok(Goldrust {
+            update_golden_files,
+            golden_file_path: GoldenPath::new(golden_file_path, extension.clone()),
+            response_source: decision.response_source.clone(),
+            save_check,
+            format: self.format,
+            codec: self.codec,
+            redactions: Vec::new(),
+            ignored: Vec::new(),
+            unordered: Vec::new(),
+            captured_headers: None,
+            comparator: None,
+            hasher: Default::default(),
+            ephemeral_dir: None,
+            float_tolerance: None,
+            normalizers: Vec::new(),
+            before_save: Vec::new(),
+            pretty: true,
+            compress: self.compress,
+            metadata: false,
+            content_addressed: self.content_addressed,
+            review,
+            forbid_update,
+            skip_unchanged: false,
+            create_dirs: self.create_dirs.unwrap_or(true),
+            sort_keys: false,
+            top_level_sort_key: None,
+            expand_embedded_json: false,
+            canonical: false,
+            ndjson_started: false,
+            sort_graphql_errors: false,
+            retry: None,
+            base_url: None,
+            external_calls: 0,
+            record_latency: false,
+            latency: None,
+            timeout: None,
+            template_vars: Default::default(),
+            golden_file_name,
+            extension,
+            golden_file_dir,
+            fallback_dirs,
+            named,
+            decision,
+            config: GoldrustConfig {
+                dir,
+                fallback_dirs: self.fallback_dirs,
+                allow_external: allow_external_api_call,
+                update: update_golden_files,
+                profile,
+            },
+        })
+    }
+}
+
+/// The golden file directory from `GOLDRUST_DIR`
+///
+/// Relative directories are resolved against `GOLDRUST_WORKSPACE_ROOT` when it is set,
+/// or `CARGO_MANIFEST_DIR` otherwise,
+/// so golden files are found regardless of the current directory of the test process.
+fn env_dir() -> PathBuf {
+    resolve_dir(
+        PathBuf::from(
+            std::env::var("GOLDRUST_DIR").unwrap_or("tests/resources/golden".to_string()),
+        ),
+        std::env::var_os("GOLDRUST_WORKSPACE_ROOT").map(PathBuf::from),
+        std::env::var_os("CARGO_MANIFEST_DIR").map(PathBuf::from),
+    )
+}
+
+/// Resolve the golden file directory against the workspace root, or the manifest directory
+///
+/// A relative workspace root is itself resolved against the manifest directory.
+/// `..` components are resolved lexically (e.g. `crate/../fixtures` → `fixtures`),
+/// so a directory outside of the crate is checked as is for existing golden files.
+fn resolve_dir(
+    dir: PathBuf,
+    workspace_root: Option<PathBuf>,
+    manifest_dir: Option<PathBuf>,
+) -> PathBuf {
+    let resolve = |path: PathBuf, base: Option<PathBuf>| match base {
+        Some(base) if path.is_relative() => base.join(path),
+        _ => path,
+    };
+    let base = match workspace_root {
+        Some(workspace_root) => Some(resolve(workspace_root, manifest_dir)),
+        None => manifest_dir,
+    };
+    let dir = resolve(dir, base);
+
+    let mut resolved = PathBuf::new();
+    for component in dir.components() {
+        match component {
+            Component::ParentDir
+                if matches!(
+                    resolved.components().next_back(),
+                    Some(Component::Normal(_))
+                ) =>
+            {
+                resolved.pop();
+            }
+            Component::CurDir => {}
+            component => resolved.push(component),
+        }
+    }
+    resolved
+}
+
+/// Strip the prefix, and the following `-` separator, from the golden file name
+///
+/// Names which don't start with the prefix, or would become empty, are kept as is.
+fn strip_name_prefix(name: String, prefix: &str) -> String {
+    match name.strip_prefix(prefix) {
+        Some(rest) => {
+            let rest = rest.strip_prefix('-').unwrap_or(rest);
+            if rest.is_empty() {
+                name
+            } else {
+                rest.to_string()
+            }
+        }
+        None => name,
+    }
+}
+
+/// Register the golden file of a name with a stripped prefix,
+/// returning an error when a different name already has the same golden file
+fn register_stripped_name(golden_file_path: &Path, name: &str) -> Result<(), GoldrustError> {
+    static NAMES: OnceLock<Mutex<HashMap<PathBuf, String>>> = OnceLock::new();
+    let mut names = NAMES
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner);
+    let path =
+        std::path::absolute(golden_file_path).unwrap_or_else(|_e| golden_file_path.to_path_buf());
+    let registered = names.entry(path).or_insert_with(|| name.to_string());
+    if registered != name {
+        return Err(GoldrustError::InvalidConfig {
+            reason: format!(
+                "{} and {} have the same golden file after stripping the name prefix: {}",
+                registered,
+                name,
+                golden_file_path.display()
+            ),
+        });
+    }
+    Ok(())
+}
+
+/// Read a boolean environment variable, defaulting to `false` when it is not set
+pub(crate) fn env_bool(name: &'static str) -> Result<bool, GoldrustError> {
+    let value = std::env::var(name).unwrap_or("false".to_string());
+    value
+        .parse()
+        .map_err(|_e| GoldrustError::InvalidEnvVar { name, value })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ResponseSource;
+
+    #[test]
+    fn build_with_explicit_configuration() {
+        let temp = crate::test_support::temp_dir();
+        let mut goldrust = Goldrust::builder()
+            .dir(temp.path().to_path_buf())
+            .allow_external_api_call(true)
+            .update_golden_files(true)
+            .golden_file_name("goldrust-build_with_explicit_configuration".to_string())
+            .build()
+            .expect("Failed to build");
+
+        assert_eq!(
+            goldrust.golden_file_path,
+            temp.path()
+                .join("goldrust-build_with_explicit_configuration.json")
+        );
+        assert_eq!(goldrust.response_source, ResponseSource::External);
+        assert!(!goldrust.save_check);
+        assert_eq!(
+            goldrust.config(),
+            &GoldrustConfig {
+                dir: temp.path().to_path_buf(),
+                fallback_dirs: Vec::new(),
+                allow_external: true,
+                update: true,
+                profile: None,
+            }
+        );
+        goldrust.disarm();
+    }
+
+    #[test]
+    fn build_with_fallback_dirs() {
+        let temp = crate::test_support::temp_dir();
+        let dir = temp.path();
+        let (local, shared) = (dir.join("local"), dir.join("shared"));
+        std::fs::create_dir_all(&shared).unwrap();
+        std::fs::write(shared.join("base.json"), r#"{"from": "shared"}"#).unwrap();
+        let build = || {
+            Goldrust::builder()
+                .dirs(vec![local.clone(), shared.clone()])
+                .allow_external_api_call(false)
+                .update_golden_files(false)
+                .golden_file_name("base".to_string())
+                .build()
+                .expect("Failed to build")
+        };
+
+        let goldrust = build();
+        assert_eq!(goldrust.response_source, ResponseSource::Local);
+        assert_eq!(goldrust.golden_file_path, local.join("base.json"));
+        assert_eq!(goldrust.config().fallback_dirs, vec![shared.clone()]);
+        goldrust.verify(&serde_json::json!({"from": "shared"}));
+
+        std::fs::create_dir_all(&local).unwrap();
+        std::fs::write(local.join("base.json"), r#"{"from": "local"}"#).unwrap();
+        build().verify(&serde_json::json!({"from": "local"}));
+    }
+
+    #[test]
+    fn build_with_named_golden_files_in_fallback_dir() {
+        let temp = crate::test_support::temp_dir();
+        let (local, shared) = (temp.path().join("local"), temp.path().join("shared"));
+        std::fs::create_dir_all(&shared).unwrap();
+        std::fs::write(shared.join("base__first.json"), r#"{"from": "shared"}"#).unwrap();
+
+        let mut goldrust = Goldrust::builder()
+            .dirs(vec![local, shared])
+            .allow_external_api_call(false)
+            .update_golden_files(false)
+            .golden_file_name("base".to_string())
+            .build()
+            .expect("Failed to build");
+        assert_eq!(goldrust.response_source, ResponseSource::Local);
+        goldrust.disarm();
+    }
+
+    #[test]
+    fn build_with_profile() {
+        let result = Goldrust::builder()
+            .dir(PathBuf::from("tests/resources/golden"))
+            .allow_external_api_call(false)
+            .update_golden_files(false)
+            .golden_file_name("base-base".to_string())
+            .profile("staging".to_string())
+            .build();
+
+        let path = PathBuf::from("tests/resources/golden/staging/base-base.json");
+        assert!(matches!(
+            result,
+            Err(GoldrustError::MissingGoldenFile { path: missing }) if missing == path
+        ));
+    }
+
+    #[test]
+    fn build_with_extension() {
+        let goldrust = Goldrust::builder()
+            .dir(PathBuf::from("tests/resources/golden"))
+            .golden_file_name("base-base".to_string())
+            .extension("golden.json".to_string())
+            .build();
+
+        assert!(matches!(
+            goldrust,
+            Err(GoldrustError::MissingGoldenFile { path })
+                if path == std::path::Path::new("tests/resources/golden/base-base.golden.json")
+        ));
+    }
+
+    #[test]
+    fn build_with_name_including_extension() {
+        let golden_file_path = |builder: GoldrustBuilder| match builder
+            .dir(PathBuf::from("tests/resources/golden"))
+            .build()
+        {
+            Err(GoldrustError::MissingGoldenFile { path }) => path,
+            result => panic!("Unexpected result: {:?}", result),
+        };
+
+        let path = golden_file_path(Goldrust::builder().golden_file_name("foo.json".to_string()));
+        assert_eq!(
+            path,
+            std::path::Path::new("tests/resources/golden/foo.json")
+        );
+
+        let path = golden_file_path(
+            Goldrust::builder()
+                .golden_file_name("foo.xml".to_string())
+                .append_extension(false),
+        );
+        assert_eq!(path, std::path::Path::new("tests/resources/golden/foo.xml"));
+
+        let path = golden_file_path(
+            Goldrust::builder()
+                .golden_file_name("foo.json".to_string())
+                .append_extension(true),
+        );
+        assert_eq!(
+            path,
+            std::path::Path::new("tests/resources/golden/foo.json.json")
+        );
+
+        let result = Goldrust::builder()
+            .golden_file_name("foo".to_string())
+            .append_extension(false)
+            .build();
+        assert!(matches!(result, Err(GoldrustError::InvalidConfig { .. })));
+    }
+
+    #[test]
+    fn env_dir_relative_to_manifest_dir() {
+        assert_eq!(
+            env_dir(),
+            std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/resources/golden")
+        );
+    }
+
+    #[test]
+    fn resolve_dir_against_workspace_root() {
+        let manifest_dir = Some(PathBuf::from("/workspace/crates/api"));
+        assert_eq!(
+            resolve_dir(PathBuf::from("fixtures"), None, manifest_dir.clone()),
+            PathBuf::from("/workspace/crates/api/fixtures")
+        );
+        assert_eq!(
+            resolve_dir(PathBuf::from("../../fixtures"), None, manifest_dir.clone()),
+            PathBuf::from("/workspace/fixtures")
+        );
+        assert_eq!(
+            resolve_dir(
+                PathBuf::from("fixtures"),
+                Some(PathBuf::from("../..")),
+                manifest_dir.clone()
+            ),
+            PathBuf::from("/workspace/fixtures")
+        );
+        assert_eq!(
+            resolve_dir(
+                PathBuf::from("/fixtures"),
+                Some(PathBuf::from("/workspace")),
+                manifest_dir
+            ),
+            PathBuf::from("/fixtures")
+        );
+    }
+
+    #[test]
+    fn build_with_name_prefix_strip() {
+        let build_in = |dir: PathBuf, name: &str| {
+            Goldrust::builder()
+                .dir(dir)
+                .allow_external_api_call(false)
+                .update_golden_files(false)
+                .name_prefix_strip("goldrust-builder".to_string())
+                .golden_file_name(name.to_string())
+                .build()
+        };
+        let build = |name: &str| build_in(PathBuf::from("tests/resources/golden"), name);
+
+        let stripped = build("goldrust-builder-base-base").expect("Failed to build");
+        assert_eq!(
+            stripped.golden_file_path,
+            std::path::Path::new("tests/resources/golden/base-base.json")
+        );
+        assert!(matches!(
+            build("base-base"),
+            Err(GoldrustError::InvalidConfig { .. })
+        ));
+        let absolute = std::path::absolute("tests/resources/golden").unwrap();
+        assert!(matches!(
+            build_in(absolute, "goldrust-builderbase-base"),
+            Err(GoldrustError::InvalidConfig { .. })
+        ));
+    }
+
+    #[test]
+    fn build_create_dirs() {
+        let temp = crate::test_support::temp_dir();
+        let dir = temp.path();
+        let build = |create_dirs: bool| {
+            Goldrust::builder()
+                .dir(dir.join("nested"))
+                .allow_external_api_call(true)
+                .update_golden_files(true)
+                .golden_file_name("golden".to_string())
+                .create_dirs(create_dirs)
+                .build()
+                .expect("Failed to build")
+        };
+
+        let mut goldrust = build(false);
+        let result = goldrust.save(serde_json::json!({}));
+        assert!(matches!(result, Err(GoldrustError::Io { .. })));
+
+        let mut goldrust = build(true);
+        goldrust
+            .save(serde_json::json!({}))
+            .expect("Failed to save");
+        assert!(goldrust.golden_file_path.exists());
+    }
+
+    #[test]
+    fn build_create_missing() {
+        let build = |name: &str| {
+            Goldrust::builder()
+                .dir(PathBuf::from("tests/resources/golden"))
+                .allow_external_api_call(true)
+                .update_golden_files(false)
+                .create_missing(true)
+                .golden_file_name(name.to_string())
+                .build()
+                .expect("Failed to build")
+        };
+
+        let existing = build("base-base");
+        assert_eq!(existing.response_source, ResponseSource::Local);
+
+        let mut missing = build("goldrust-build_create_missing");
+        assert_eq!(missing.response_source, ResponseSource::External);
+        assert!(!missing.save_check);
+        missing.disarm();
+    }
+
+    #[test]
+    fn build_invalid_combination() {
+        let result = Goldrust::builder()
+            .allow_external_api_call(false)
+            .update_golden_files(true)
+            .golden_file_name("goldrust-build_invalid_combination".to_string())
+            .build();
+
+        assert!(matches!(result, Err(GoldrustError::InvalidConfig { .. })));
+    }
+}
@@ -0,0 +1,46 @@
+use serde_json::{Number, Value};
+
+/// The largest magnitude below which every integer is exactly representable as a float
+const MAX_SAFE_INTEGER: f64 = 9_007_199_254_740_992.0;
+
+/// Canonicalize the value, so the same logical content always serializes to the same bytes
+///
+/// Object keys are sorted recursively,
+/// and floats without a fractional part (including `-0.0`) are written as integers,
+/// e.g. `1.0` → `1`.
+/// Other floats already serialize to their shortest round-trip representation.
+pub(crate) fn canonicalize(value: &mut Value) {
+    value.sort_all_objects();
+    canonicalize_numbers(value);
+}
+
+fn canonicalize_numbers(value: &mut Value) {
+    match value {
+        Value::Number(number) => {
+            if let Some(float) = number.as_f64().filter(|_| number.is_f64()) {
+                if float.fract() == 0.0 && float.abs() < MAX_SAFE_INTEGER {
+                    *number = Number::from(float as i64);
+                }
+            }
+        }
+        Value::Array(values) => values.iter_mut().for_each(canonicalize_numbers),
+        Value::Object(map) => map.values_mut().for_each(canonicalize_numbers),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn canonicalize_sorts_keys_and_integral_floats() {
+        let mut value = json!({"b": [1.0, -0.0, 1.5], "a": {"d": 2e3, "c": 1}});
+        canonicalize(&mut value);
+        assert_eq!(
+            serde_json::to_string(&value).unwrap(),
+            r#"{"a":{"c":1,"d":2000},"b":[1,0,1.5]}"#
+        );
+    }
+}
@@ -0,0 +1,132 @@
+use crate::{GoldenFormat, GoldrustError};
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::cmp::Ordering;
+use std::sync::Arc;
+
+/// The error returned by a [`GoldenCodec`]
+pub type CodecError = Box<dyn std::error::Error + Send + Sync>;
+
+/// A pluggable serializer for golden files
+///
+/// Implement this to save golden files in formats which [`GoldenFormat`] doesn't cover,
+/// such as protobuf JSON or canonical JSON,
+/// and set it with [`GoldrustBuilder::codec`](crate::GoldrustBuilder::codec).
+/// Errors are returned as [`GoldrustError::Serialization`].
+pub trait GoldenCodec: std::fmt::Debug + Send + Sync {
+    /// The extension of the golden files, without the leading dot
+    fn extension(&self) -> &str;
+
+    /// Serialize the value into the content of the golden file
+    fn serialize(&self, value: &Value) -> Result<Vec<u8>, CodecError>;
+
+    /// Deserialize the content of the golden file into a value
+    fn deserialize(&self, bytes: &[u8]) -> Result<Value, CodecError>;
+}
+
+/// The default codec, saving pretty-printed JSON golden files
+#[derive(Clone, Copy, Eq, PartialEq, Debug, Default)]
+pub struct JsonCodec;
+
+impl GoldenCodec for JsonCodec {
+    fn extension(&self) -> &str {
+        GoldenFormat::Json.extension()
+    }
+
+    fn serialize(&self, value: &Value) -> Result<Vec<u8>, CodecError> {
+        Ok(serde_json::to_vec_pretty(value)?)
+    }
+
+    fn deserialize(&self, bytes: &[u8]) -> Result<Value, CodecError> {
+        Ok(serde_json::from_slice(bytes)?)
+    }
+}
+
+/// A shared [`GoldenCodec`]
+///
+/// Codecs are compared by identity,
+/// so that [`Goldrust`](crate::Goldrust) keeps implementing the common traits.
+#[derive(Clone, Debug)]
+pub(crate) struct Codec(pub(crate) Arc<dyn GoldenCodec>);
+
+impl Codec {
+    fn address(&self) -> *const () {
+        Arc::as_ptr(&self.0) as *const ()
+    }
+}
+
+impl PartialEq for Codec {
+    fn eq(&self, other: &Self) -> bool {
+        self.address() == other.address()
+    }
+}
+
+impl Eq for Codec {}
+
+impl PartialOrd for Codec {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Codec {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.address().cmp(&other.address())
+    }
+}
+
+/// Serialize the value with the codec, or with the format when there is no codec
+pub(crate) fn serialize(
+    format: GoldenFormat,
+    codec: Option<&Codec>,
+    value: &Value,
+    pretty: bool,
+) -> Result<Vec<u8>, GoldrustError> {
+    match codec {
+        Some(codec) => codec
+            .0
+            .serialize(value)
+            .map_err(|source| GoldrustError::Serialization { source }),
+        None => {
+            let mut buf = Vec::new();
+            format.write(&mut buf, value, pretty)?;
+            Ok(buf)
+        }
+    }
+}
+
+/// Deserialize the bytes with the codec, or with the format when there is no codec
+pub(crate) fn deserialize<T>(
+    format: GoldenFormat,
+    codec: Option<&Codec>,
+    bytes: &[u8],
+) -> Result<T, GoldrustError>
+where
+    T: DeserializeOwned,
+{
+    match codec {
+        Some(codec) => {
+            let value = codec
+                .0
+                .deserialize(bytes)
+                .map_err(|source| GoldrustError::Serialization { source })?;
+            Ok(serde_json::from_value(value)?)
+        }
+        None => format.read(bytes),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn json_codec_round_trip() {
+        let codec = Codec(Arc::new(JsonCodec));
+        let value = serde_json::json!({"name": "June", "age": 1});
+        let bytes = serialize(GoldenFormat::Yaml, Some(&codec), &value, false).unwrap();
+        assert!(bytes.starts_with(b"{\n"));
+        let read: Value = deserialize(GoldenFormat::Yaml, Some(&codec), &bytes).unwrap();
+        assert_eq!(read, value);
+    }
+}
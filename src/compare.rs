@@ -0,0 +1,478 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Number, Value};
+use std::cmp::Ordering;
+use std::io::Read;
+
+/// A custom equality of the golden and actual values
+///
+/// Comparators are compared by address,
+/// so that [`Goldrust`](crate::Goldrust) keeps implementing the common traits.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Comparator(pub(crate) fn(&Value, &Value) -> bool);
+
+impl Comparator {
+    fn address(&self) -> *const () {
+        self.0 as *const ()
+    }
+}
+
+impl PartialEq for Comparator {
+    fn eq(&self, other: &Self) -> bool {
+        self.address() == other.address()
+    }
+}
+
+impl Eq for Comparator {}
+
+impl PartialOrd for Comparator {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Comparator {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.address().cmp(&other.address())
+    }
+}
+
+/// The tolerance within which two floats are considered equal
+///
+/// Tolerances are compared with the total order of floats,
+/// so that [`Goldrust`](crate::Goldrust) keeps implementing the common traits.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub(crate) struct FloatTolerance(pub(crate) f64);
+
+impl PartialEq for FloatTolerance {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for FloatTolerance {}
+
+impl PartialOrd for FloatTolerance {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for FloatTolerance {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.total_cmp(&other.0)
+    }
+}
+
+/// Replace the floats in `actual` which are within the tolerance of `golden` with the golden floats
+///
+/// Values are matched by object key and array index.
+/// Integers on both sides are left as is, so they are still compared exactly.
+pub(crate) fn apply_tolerance(golden: &Value, actual: &mut Value, tolerance: FloatTolerance) {
+    match (golden, actual) {
+        (Value::Object(golden), Value::Object(actual)) => {
+            for (key, actual) in actual.iter_mut() {
+                if let Some(golden) = golden.get(key) {
+                    apply_tolerance(golden, actual, tolerance);
+                }
+            }
+        }
+        (Value::Array(golden), Value::Array(actual)) => {
+            for (golden, actual) in golden.iter().zip(actual.iter_mut()) {
+                apply_tolerance(golden, actual, tolerance);
+            }
+        }
+        (Value::Number(golden), actual) if within_tolerance(golden, actual, tolerance) => {
+            *actual = Value::Number(golden.clone());
+        }
+        _ => {}
+    }
+}
+
+/// Whether `actual` is a number within the tolerance of `golden`, and either of them is a float
+fn within_tolerance(golden: &Number, actual: &Value, tolerance: FloatTolerance) -> bool {
+    let Value::Number(actual) = actual else {
+        return false;
+    };
+    if !golden.is_f64() && !actual.is_f64() {
+        return false;
+    }
+    match (golden.as_f64(), actual.as_f64()) {
+        (Some(golden), Some(actual)) => (golden - actual).abs() <= tolerance.0,
+        _ => false,
+    }
+}
+
+/// The size of the chunks read while comparing streams
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// The offset of the first byte where the streams differ, or `None` when they are equal
+///
+/// Both streams are read in fixed-size chunks, so neither is held in memory as a whole.
+/// When one stream is a prefix of the other, the offset is the length of the shorter stream.
+pub(crate) fn first_difference(
+    mut expected: impl Read,
+    mut actual: impl Read,
+) -> std::io::Result<Option<u64>> {
+    let mut expected_chunk = vec![0; CHUNK_SIZE];
+    let mut actual_chunk = vec![0; CHUNK_SIZE];
+    let mut offset = 0;
+    loop {
+        let expected_len = read_chunk(&mut expected, &mut expected_chunk)?;
+        let actual_len = read_chunk(&mut actual, &mut actual_chunk)?;
+        let position = expected_chunk[..expected_len]
+            .iter()
+            .zip(&actual_chunk[..actual_len])
+            .position(|(expected, actual)| expected != actual);
+        if let Some(position) = position {
+            return Ok(Some(offset + position as u64));
+        }
+        if expected_len != actual_len {
+            return Ok(Some(offset + expected_len.min(actual_len) as u64));
+        }
+        if expected_len == 0 {
+            return Ok(None);
+        }
+        offset += expected_len as u64;
+    }
+}
+
+/// Fill the chunk from the reader, returning the length read, which is shorter only at the end
+fn read_chunk(reader: &mut impl Read, chunk: &mut [u8]) -> std::io::Result<usize> {
+    let mut filled = 0;
+    while filled < chunk.len() {
+        match reader.read(&mut chunk[filled..]) {
+            Ok(0) => break,
+            Ok(len) => filled += len,
+            Err(e) if e.kind() == std::io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(filled)
+}
+
+/// The value which ignored fields are replaced with before comparing
+const IGNORED: &str = "<ignored>";
+
+/// Replace the values at each path with [`IGNORED`]
+pub(crate) fn ignore(value: &mut Value, paths: &[String]) {
+    for path in paths {
+        crate::path::visit_mut(value, path, |value| {
+            *value = Value::String(IGNORED.to_string());
+        });
+    }
+}
+
+/// Sort the arrays at each path, by the canonical serialization of the elements
+///
+/// Values at the paths which are not arrays are kept as is.
+pub(crate) fn sort_unordered(value: &mut Value, paths: &[String]) {
+    for path in paths {
+        crate::path::visit_mut(value, path, |value| {
+            if let Value::Array(values) = value {
+                values.sort_by_cached_key(|value| {
+                    let mut value = value.clone();
+                    crate::canonical::canonicalize(&mut value);
+                    value.to_string()
+                });
+            }
+        });
+    }
+}
+
+/// Sort the elements of a top-level array by the value at the key path, e.g. `id` or `user.id`
+///
+/// Numbers are compared numerically and other values by their serialization,
+/// and elements without the key come last, in their original order.
+/// Values which are not arrays are kept as is.
+pub(crate) fn sort_top_level_by(value: &mut Value, key: &str) {
+    let Value::Array(values) = value else {
+        return;
+    };
+    // Each segment is escaped as in RFC 6901, so keys may contain `/` or `~`
+    let pointer: String = key
+        .split('.')
+        .map(|segment| format!("/{}", segment.replace('~', "~0").replace('/', "~1")))
+        .collect();
+    values.sort_by(|a, b| match (a.pointer(&pointer), b.pointer(&pointer)) {
+        (Some(a), Some(b)) => compare_keys(a, b),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    });
+}
+
+fn compare_keys(a: &Value, b: &Value) -> Ordering {
+    match (a, b) {
+        (Value::Number(a), Value::Number(b)) => compare_numbers(a, b),
+        (Value::String(a), Value::String(b)) => a.cmp(b),
+        (a, b) => a.to_string().cmp(&b.to_string()),
+    }
+}
+
+/// Compare integers exactly, even above 2^53, and other numbers as floats
+fn compare_numbers(a: &Number, b: &Number) -> Ordering {
+    let integer = |number: &Number| {
+        number
+            .as_i64()
+            .map(i128::from)
+            .or_else(|| number.as_u64().map(i128::from))
+    };
+    match (integer(a), integer(b)) {
+        (Some(a), Some(b)) => a.cmp(&b),
+        _ => a
+            .as_f64()
+            .unwrap_or_default()
+            .total_cmp(&b.as_f64().unwrap_or_default()),
+    }
+}
+
+/// Join a parent path and an object key, in the path syntax of [`crate::Goldrust::redact`]
+fn key_path(parent: &str, key: &str) -> String {
+    if parent.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", parent, key)
+    }
+}
+
+/// Join a parent path and an array index
+fn index_path(parent: &str, index: usize) -> String {
+    format!("{}[{}]", parent, index)
+}
+
+/// Display a path, where the empty path is the root
+fn display_path(path: &str) -> &str {
+    if path.is_empty() {
+        "<root>"
+    } else {
+        path
+    }
+}
+
+/// Collect the paths where `expected` is not a subset of `actual`
+///
+/// Objects in `actual` may have extra keys.
+/// Arrays must have the same length, and each element is compared as a subset.
+/// Other values must be equal.
+pub(crate) fn subset_mismatches(expected: &Value, actual: &Value) -> Vec<String> {
+    let mut mismatches = Vec::new();
+    collect_subset_mismatches(expected, actual, "", &mut mismatches);
+    mismatches
+}
+
+fn collect_subset_mismatches(
+    expected: &Value,
+    actual: &Value,
+    path: &str,
+    mismatches: &mut Vec<String>,
+) {
+    match (expected, actual) {
+        (Value::Object(expected), Value::Object(actual)) => {
+            for (key, expected) in expected {
+                let path = key_path(path, key);
+                match actual.get(key) {
+                    Some(actual) => collect_subset_mismatches(expected, actual, &path, mismatches),
+                    None => mismatches.push(format!("{}: missing", path)),
+                }
+            }
+        }
+        (Value::Array(expected), Value::Array(actual)) if expected.len() == actual.len() => {
+            for (index, (expected, actual)) in expected.iter().zip(actual).enumerate() {
+                collect_subset_mismatches(expected, actual, &index_path(path, index), mismatches);
+            }
+        }
+        (expected, actual) if expected != actual => mismatches.push(format!(
+            "{}: expected {}, got {}",
+            display_path(path),
+            expected,
+            actual
+        )),
+        _ => {}
+    }
+}
+
+/// Collect the differences between `expected` and `actual`, path by path
+///
+/// Each difference is a line starting with:
+/// - `-`: The path was removed, only existing in `expected`
+/// - `+`: The path was added, only existing in `actual`
+/// - `~`: The value at the path changed
+pub(crate) fn diff(expected: &Value, actual: &Value) -> Vec<String> {
+    let mut differences = Vec::new();
+    collect_differences(expected, actual, "", &mut differences);
+    differences
+}
+
+fn collect_differences(
+    expected: &Value,
+    actual: &Value,
+    path: &str,
+    differences: &mut Vec<String>,
+) {
+    match (expected, actual) {
+        (Value::Object(expected_map), Value::Object(actual_map)) => {
+            for (key, expected) in expected_map {
+                let path = key_path(path, key);
+                match actual_map.get(key) {
+                    Some(actual) => collect_differences(expected, actual, &path, differences),
+                    None => differences.push(format!("- {}: {}", path, expected)),
+                }
+            }
+            for (key, actual) in actual_map {
+                if !expected_map.contains_key(key) {
+                    differences.push(format!("+ {}: {}", key_path(path, key), actual));
+                }
+            }
+        }
+        (Value::Array(expected_values), Value::Array(actual_values)) => {
+            for (index, expected) in expected_values.iter().enumerate() {
+                let path = index_path(path, index);
+                match actual_values.get(index) {
+                    Some(actual) => collect_differences(expected, actual, &path, differences),
+                    None => differences.push(format!("- {}: {}", path, expected)),
+                }
+            }
+            for (index, actual) in actual_values.iter().enumerate().skip(expected_values.len()) {
+                differences.push(format!("+ {}: {}", index_path(path, index), actual));
+            }
+        }
+        (expected, actual) if expected != actual => differences.push(format!(
+            "~ {}: {} -> {}",
+            display_path(path),
+            expected,
+            actual
+        )),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn first_difference_across_chunks() {
+        let expected = vec![1u8; CHUNK_SIZE * 2 + 10];
+        let mut actual = expected.clone();
+        assert_eq!(
+            first_difference(expected.as_slice(), actual.as_slice()).unwrap(),
+            None
+        );
+
+        actual[CHUNK_SIZE + 3] = 2;
+        assert_eq!(
+            first_difference(expected.as_slice(), actual.as_slice()).unwrap(),
+            Some(CHUNK_SIZE as u64 + 3)
+        );
+        assert_eq!(
+            first_difference(expected.as_slice(), &expected[..CHUNK_SIZE]).unwrap(),
+            Some(CHUNK_SIZE as u64)
+        );
+    }
+
+    #[test]
+    fn sort_unordered_arrays() {
+        let mut value = json!({
+            "tags": ["b", "a"],
+            "users": [{"permissions": [{"name": "write"}, {"name": "read"}]}],
+            "ordered": [2, 1],
+        });
+        sort_unordered(
+            &mut value,
+            &["tags".to_string(), "users[*].permissions".to_string()],
+        );
+        assert_eq!(
+            value,
+            json!({
+                "tags": ["a", "b"],
+                "users": [{"permissions": [{"name": "read"}, {"name": "write"}]}],
+                "ordered": [2, 1],
+            })
+        );
+    }
+
+    #[test]
+    fn sort_top_level_array_by_key() {
+        let mut value = json!([
+            {"id": 10},
+            {"name": "no id"},
+            {"id": 9},
+            {"id": 1.5},
+        ]);
+        sort_top_level_by(&mut value, "id");
+        assert_eq!(
+            value,
+            json!([{"id": 1.5}, {"id": 9}, {"id": 10}, {"name": "no id"}])
+        );
+
+        let mut value = json!([{"user": {"name": "b"}}, {"user": {"name": "a"}}]);
+        sort_top_level_by(&mut value, "user.name");
+        assert_eq!(
+            value,
+            json!([{"user": {"name": "a"}}, {"user": {"name": "b"}}])
+        );
+
+        let mut value = json!([{"a/b~c": 2}, {"a/b~c": 1}]);
+        sort_top_level_by(&mut value, "a/b~c");
+        assert_eq!(value, json!([{"a/b~c": 1}, {"a/b~c": 2}]));
+
+        let mut value = json!([{"id": 9007199254740993u64}, {"id": 9007199254740992u64}]);
+        sort_top_level_by(&mut value, "id");
+        assert_eq!(
+            value,
+            json!([{"id": 9007199254740992u64}, {"id": 9007199254740993u64}])
+        );
+    }
+
+    #[test]
+    fn apply_tolerance_to_floats() {
+        let golden = json!({"price": 1.0000001, "count": 1, "items": [{"ratio": 0.5}]});
+        let mut actual = json!({"price": 1.0000002, "count": 2, "items": [{"ratio": 0.6}]});
+        apply_tolerance(&golden, &mut actual, FloatTolerance(1e-6));
+        assert_eq!(
+            actual,
+            json!({"price": 1.0000001, "count": 2, "items": [{"ratio": 0.6}]})
+        );
+
+        let mut actual = json!({"count": 2});
+        apply_tolerance(&json!({"count": 1}), &mut actual, FloatTolerance(10.0));
+        assert_eq!(actual, json!({"count": 2}));
+    }
+
+    #[test]
+    fn subset_ignores_extra_keys() {
+        let expected = json!({"name": "June", "items": [{"id": 1}]});
+        let actual = json!({"name": "June", "age": 1, "items": [{"id": 1, "new": true}]});
+        assert!(subset_mismatches(&expected, &actual).is_empty());
+    }
+
+    #[test]
+    fn subset_reports_paths() {
+        let expected = json!({"name": "June", "items": [{"id": 1}], "age": 1});
+        let actual = json!({"name": "July", "items": [{"id": 2}]});
+        assert_eq!(
+            subset_mismatches(&expected, &actual),
+            vec![
+                r#"name: expected "June", got "July""#,
+                "items[0].id: expected 1, got 2",
+                "age: missing",
+            ]
+        );
+    }
+
+    #[test]
+    fn diff_reports_added_removed_changed() {
+        let expected = json!({"name": "June", "age": 1, "tags": ["a"]});
+        let actual = json!({"name": "July", "tags": ["a", "b"], "email": "june@example.com"});
+        assert_eq!(
+            diff(&expected, &actual),
+            vec![
+                r#"~ name: "June" -> "July""#,
+                "- age: 1",
+                r#"+ tags[1]: "b""#,
+                r#"+ email: "june@example.com""#,
+            ]
+        );
+    }
+}
@@ -0,0 +1,32 @@
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use std::io::{Error, Read, Write};
+
+/// The extension appended to compressed golden files, without the leading dot
+pub(crate) const EXTENSION: &str = "gz";
+
+/// Compress the bytes with gzip
+pub(crate) fn compress(bytes: &[u8]) -> Result<Vec<u8>, Error> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(bytes)?;
+    encoder.finish()
+}
+
+/// Decompress gzip compressed bytes
+pub(crate) fn decompress(bytes: &[u8]) -> Result<Vec<u8>, Error> {
+    let mut decompressed = Vec::new();
+    GzDecoder::new(bytes).read_to_end(&mut decompressed)?;
+    Ok(decompressed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let bytes = br#"{"name": "June", "age": 1}"#;
+        assert_eq!(decompress(&compress(bytes).unwrap()).unwrap(), bytes);
+    }
+}
@@ -0,0 +1,21 @@
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// The effective configuration of a [`Goldrust`](crate::Goldrust) instance
+///
+/// Configurations are resolved from [`GoldrustBuilder`](crate::GoldrustBuilder),
+/// falling back to the environment variables,
+/// so this is useful for asserting on or logging the configuration in tests.
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Debug, Serialize, Deserialize)]
+pub struct GoldrustConfig {
+    /// The directory of the golden files, without the profile
+    pub dir: PathBuf,
+    /// The directories golden files are read from when `dir` doesn't have them, without the profile
+    pub fallback_dirs: Vec<PathBuf>,
+    /// Whether external api calls are allowed
+    pub allow_external: bool,
+    /// Whether golden files are updated, including when creating missing golden files
+    pub update: bool,
+    /// The profile, which keeps golden files in a subdirectory of `dir`
+    pub profile: Option<String>,
+}
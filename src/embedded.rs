@@ -0,0 +1,87 @@
+use serde_json::{Map, Value};
+
+/// The key of the object which holds an expanded JSON-in-string value
+///
+/// e.g. `"payload": "{\"id\":1}"` is stored as `"payload": {"$embedded_json": {"id": 1}}`.
+pub(crate) const KEY: &str = "$embedded_json";
+
+/// The value embedded in the string, when it is a JSON object or array
+fn parse(text: &str) -> Option<Value> {
+    let trimmed = text.trim_start();
+    if !trimmed.starts_with('{') && !trimmed.starts_with('[') {
+        return None;
+    }
+    serde_json::from_str(text).ok()
+}
+
+/// Expand strings holding a JSON object or array, recursively
+pub(crate) fn expand(value: &mut Value) {
+    match value {
+        Value::String(text) => {
+            if let Some(mut embedded) = parse(text) {
+                expand(&mut embedded);
+                *value = Value::Object(Map::from_iter([(KEY.to_string(), embedded)]));
+            }
+        }
+        Value::Array(values) => values.iter_mut().for_each(expand),
+        Value::Object(map) => map.values_mut().for_each(expand),
+        _ => {}
+    }
+}
+
+/// Restore expanded values into compact JSON strings, recursively
+pub(crate) fn restore(value: &mut Value) {
+    match value {
+        Value::Object(map) if map.len() == 1 && map.contains_key(KEY) => {
+            let mut embedded = map.remove(KEY).unwrap_or_default();
+            restore(&mut embedded);
+            *value = Value::String(embedded.to_string());
+        }
+        Value::Array(values) => values.iter_mut().for_each(restore),
+        Value::Object(map) => map.values_mut().for_each(restore),
+        _ => {}
+    }
+}
+
+/// Rewrite strings holding a JSON object or array in the compact form, recursively
+///
+/// This is the form [`restore`] produces, so whitespace in the embedded JSON doesn't matter.
+pub(crate) fn compact(value: &mut Value) {
+    expand(value);
+    restore(value);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn expand_then_restore() {
+        let original = json!({
+            "payload": "{\"id\":1,\"nested\":\"[1,2]\"}",
+            "items": ["[true]", "not json", "{broken"],
+            "number": "1",
+        });
+        let mut value = original.clone();
+        expand(&mut value);
+        assert_eq!(
+            value,
+            json!({
+                "payload": {KEY: {"id": 1, "nested": {KEY: [1, 2]}}},
+                "items": [{KEY: [true]}, "not json", "{broken"],
+                "number": "1",
+            })
+        );
+
+        restore(&mut value);
+        assert_eq!(value, original);
+    }
+
+    #[test]
+    fn compact_embedded_whitespace() {
+        let mut value = json!({"payload": "{ \"id\": 1 }"});
+        compact(&mut value);
+        assert_eq!(value, json!({"payload": "{\"id\":1}"}));
+    }
+}
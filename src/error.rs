@@ -0,0 +1,150 @@
+use crate::GoldenFormat;
+use derive_more::{Display, Error};
+use std::path::PathBuf;
+
+/// Errors which can occur while configuring or using Goldrust
+#[derive(Debug, Display, Error)]
+pub enum GoldrustError {
+    /// An environment variable could not be parsed into the expected type
+    #[display("{name} must be parseable as a boolean, got {value:?}")]
+    InvalidEnvVar { name: &'static str, value: String },
+    /// The configuration is invalid, or is an invalid combination
+    #[display("{reason}")]
+    InvalidConfig { reason: String },
+    /// A golden file is required, but does not exist
+    #[display("Cannot test without allowing external API calls when golden files do not exist, create file: {}", path.display())]
+    MissingGoldenFile { path: PathBuf },
+    /// The content can't be represented in the golden file format
+    #[display("Content can't be saved as {format}: {reason}")]
+    UnsupportedFormat {
+        format: GoldenFormat,
+        reason: String,
+    },
+    /// The golden file no longer deserializes into the expected type,
+    /// which usually means the golden file should be re-recorded
+    #[display("Golden file does not deserialize into {expected_type}, re-record the golden file: {serde_error}")]
+    SchemaMismatch {
+        expected_type: &'static str,
+        serde_error: String,
+    },
+    /// A golden file exists, but is empty or can't be parsed,
+    /// e.g. after a crashed save
+    #[display("Golden file {} is corrupt, re-record the golden file: {reason}", path.display())]
+    CorruptGolden { path: PathBuf, reason: String },
+    /// A golden file would be written, while updating golden files is forbidden
+    #[display("Updating golden files is forbidden, tried to write: {}", path.display())]
+    UpdateForbidden { path: PathBuf },
+    /// The external API call didn't finish within the timeout
+    #[display("External API call timed out after {timeout:?}")]
+    ExternalTimeout { timeout: std::time::Duration },
+    /// The external API call still failed after the attempts of the [`RetryPolicy`](crate::RetryPolicy)
+    #[display("External API call failed: {reason}")]
+    ExternalFailed { reason: String },
+    /// Reading the response failed
+    #[cfg(feature = "reqwest")]
+    #[display("HTTP error: {source}")]
+    Http { source: reqwest::Error },
+    /// The body is not a GraphQL response envelope
+    #[display("Invalid GraphQL response: {reason}")]
+    InvalidGraphQlResponse { reason: String },
+    /// Reading or writing a golden file failed
+    #[display("IO error: {source}")]
+    Io { source: std::io::Error },
+    /// The content could not be serialized into, or deserialized from, the golden file format
+    #[display("Serialization error: {source}")]
+    Serialization {
+        source: Box<dyn std::error::Error + Send + Sync>,
+    },
+}
+
+impl From<std::io::Error> for GoldrustError {
+    fn from(source: std::io::Error) -> Self {
+        GoldrustError::Io { source }
+    }
+}
+
+impl From<serde_json::Error> for GoldrustError {
+    fn from(source: serde_json::Error) -> Self {
+        if source.is_io() {
+            GoldrustError::Io {
+                source: source.into(),
+            }
+        } else {
+            GoldrustError::Serialization {
+                source: Box::new(source),
+            }
+        }
+    }
+}
+
+impl From<serde_yaml::Error> for GoldrustError {
+    fn from(source: serde_yaml::Error) -> Self {
+        GoldrustError::Serialization {
+            source: Box::new(source),
+        }
+    }
+}
+
+impl From<toml::ser::Error> for GoldrustError {
+    fn from(source: toml::ser::Error) -> Self {
+        GoldrustError::Serialization {
+            source: Box::new(source),
+        }
+    }
+}
+
+impl From<toml::de::Error> for GoldrustError {
+    fn from(source: toml::de::Error) -> Self {
+        GoldrustError::Serialization {
+            source: Box::new(source),
+        }
+    }
+}
+
+impl From<quick_xml::DeError> for GoldrustError {
+    fn from(source: quick_xml::DeError) -> Self {
+        GoldrustError::Serialization {
+            source: Box::new(source),
+        }
+    }
+}
+
+impl From<rmp_serde::encode::Error> for GoldrustError {
+    fn from(source: rmp_serde::encode::Error) -> Self {
+        GoldrustError::Serialization {
+            source: Box::new(source),
+        }
+    }
+}
+
+impl From<rmp_serde::decode::Error> for GoldrustError {
+    fn from(source: rmp_serde::decode::Error) -> Self {
+        GoldrustError::Serialization {
+            source: Box::new(source),
+        }
+    }
+}
+
+#[cfg(feature = "grpc")]
+impl From<prost::DecodeError> for GoldrustError {
+    fn from(source: prost::DecodeError) -> Self {
+        GoldrustError::Serialization {
+            source: Box::new(source),
+        }
+    }
+}
+
+#[cfg(feature = "reqwest")]
+impl From<reqwest::Error> for GoldrustError {
+    fn from(source: reqwest::Error) -> Self {
+        GoldrustError::Http { source }
+    }
+}
+
+impl From<base64::DecodeError> for GoldrustError {
+    fn from(source: base64::DecodeError) -> Self {
+        GoldrustError::Serialization {
+            source: Box::new(source),
+        }
+    }
+}
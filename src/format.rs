@@ -0,0 +1,294 @@
+use crate::GoldrustError;
+use derive_more::Display;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::io::{BufReader, Read, Write};
+
+/// The name of the root element of XML golden files
+const XML_ROOT: &str = "golden";
+
+/// The file format of the golden files
+///
+/// The format decides both the serialization backend used in `save`
+/// and the extension of the golden file.
+///
+/// # MessagePack
+///
+/// MessagePack golden files are compact binary files,
+/// which keep large payloads small.
+/// As they aren't human-readable, compare by deserializing them
+/// (e.g. with [`Goldrust::assert_matches`](crate::Goldrust::assert_matches))
+/// rather than comparing strings.
+///
+/// # Text
+///
+/// Text golden files hold the text verbatim, for non-structured bodies such as HTML.
+/// Use [`Goldrust::save_str`](crate::Goldrust::save_str) to save them,
+/// as serde based saving returns [`GoldrustError::UnsupportedFormat`]
+/// for content which is not a string.
+///
+/// # XML
+///
+/// XML golden files are pretty-printed when configured, with `golden` as the root element.
+/// XML has no direct equivalent of a JSON value, so:
+/// - Every value is text, e.g. numbers and booleans are read back as strings
+///   unless they are deserialized into a typed field.
+///   Compare with [`Goldrust::verify`](crate::Goldrust::verify),
+///   which deserializes the golden file into the user's type instead of a JSON value.
+/// - Attributes and text content follow the `quick-xml` naming conventions,
+///   i.e. fields renamed to `@name` are attributes, and a field renamed to `$text` is the text.
+/// - Namespaces are not resolved, so prefixes are part of the element names (e.g. `soap:Body`).
+/// - `None` fields are omitted, and a top-level sequence can't be represented.
+///
+/// # TOML
+///
+/// TOML can't represent every value,
+/// so saving returns [`GoldrustError::UnsupportedFormat`] when:
+/// - The top-level value is not a table (e.g. a top-level array)
+/// - The content contains a `null` (e.g. a `None` field)
+#[derive(
+    Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Debug, Default, Serialize, Deserialize, Display,
+)]
+#[display("{_variant}")]
+pub enum GoldenFormat {
+    #[default]
+    Json,
+    Yaml,
+    Toml,
+    MessagePack,
+    Text,
+    Xml,
+}
+
+impl GoldenFormat {
+    /// The extension of the golden file, without the leading dot
+    pub fn extension(&self) -> &'static str {
+        match self {
+            GoldenFormat::Json => "json",
+            GoldenFormat::Yaml => "yaml",
+            GoldenFormat::Toml => "toml",
+            GoldenFormat::MessagePack => "msgpack",
+            GoldenFormat::Text => "txt",
+            GoldenFormat::Xml => "xml",
+        }
+    }
+
+    /// The format for bodies of the `Content-Type`, if there is one
+    ///
+    /// `application/json` and `+json` types are JSON,
+    /// `application/xml`, `text/xml` and `+xml` types are XML,
+    /// and other `text/*` types are text.
+    /// Parameters such as `charset` are ignored.
+    pub fn from_content_type(content_type: &str) -> Option<Self> {
+        let media_type = content_type
+            .split(';')
+            .next()
+            .unwrap_or_default()
+            .trim()
+            .to_ascii_lowercase();
+        if media_type == "application/json" || media_type.ends_with("+json") {
+            Some(GoldenFormat::Json)
+        } else if media_type == "application/xml"
+            || media_type == "text/xml"
+            || media_type.ends_with("+xml")
+        {
+            Some(GoldenFormat::Xml)
+        } else if media_type.starts_with("text/") {
+            Some(GoldenFormat::Text)
+        } else {
+            None
+        }
+    }
+
+    /// Serialize content to the writer in this format
+    ///
+    /// `pretty` only applies to formats which have a compact representation.
+    pub(crate) fn write<W, T>(
+        &self,
+        mut writer: W,
+        content: &T,
+        pretty: bool,
+    ) -> Result<(), GoldrustError>
+    where
+        W: Write,
+        T: Serialize,
+    {
+        match self {
+            GoldenFormat::Json if pretty => serde_json::to_writer_pretty(writer, content)?,
+            GoldenFormat::Json => serde_json::to_writer(writer, content)?,
+            GoldenFormat::Yaml => serde_yaml::to_writer(writer, content)?,
+            GoldenFormat::Toml => {
+                let value = serde_json::to_value(content)?;
+                check_toml(&value)?;
+                let toml = if pretty {
+                    toml::to_string_pretty(&value)?
+                } else {
+                    toml::to_string(&value)?
+                };
+                writer.write_all(toml.as_bytes())?;
+            }
+            GoldenFormat::MessagePack => rmp_serde::encode::write_named(&mut writer, content)?,
+            GoldenFormat::Text => match serde_json::to_value(content)? {
+                serde_json::Value::String(text) => writer.write_all(text.as_bytes())?,
+                _ => {
+                    return Err(GoldrustError::UnsupportedFormat {
+                        format: GoldenFormat::Text,
+                        reason: "Only strings can be saved as text".to_string(),
+                    })
+                }
+            },
+            GoldenFormat::Xml => {
+                let mut xml = String::new();
+                let mut serializer =
+                    quick_xml::se::Serializer::with_root(&mut xml, Some(XML_ROOT))?;
+                if pretty {
+                    serializer.indent(' ', 2);
+                }
+                content.serialize(serializer)?;
+                writer.write_all(xml.as_bytes())?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Deserialize content from the reader in this format
+    pub(crate) fn read<R, T>(&self, mut reader: R) -> Result<T, GoldrustError>
+    where
+        R: Read,
+        T: DeserializeOwned,
+    {
+        let content = match self {
+            GoldenFormat::Json => serde_json::from_reader(reader)?,
+            GoldenFormat::Yaml => serde_yaml::from_reader(reader)?,
+            GoldenFormat::Toml => {
+                let mut toml = String::new();
+                reader.read_to_string(&mut toml)?;
+                toml::from_str(&toml)?
+            }
+            GoldenFormat::MessagePack => rmp_serde::from_read(reader)?,
+            GoldenFormat::Text => {
+                let mut text = String::new();
+                reader.read_to_string(&mut text)?;
+                serde_json::from_value(serde_json::Value::String(text))?
+            }
+            GoldenFormat::Xml => quick_xml::de::from_reader(BufReader::new(reader))?,
+        };
+        Ok(content)
+    }
+}
+
+/// Check that the value can be represented in TOML
+fn check_toml(value: &serde_json::Value) -> Result<(), GoldrustError> {
+    fn contains_null(value: &serde_json::Value) -> bool {
+        match value {
+            serde_json::Value::Null => true,
+            serde_json::Value::Array(values) => values.iter().any(contains_null),
+            serde_json::Value::Object(map) => map.values().any(contains_null),
+            _ => false,
+        }
+    }
+
+    let reason = if !value.is_object() {
+        "The top-level value must be a table"
+    } else if contains_null(value) {
+        "Null values can't be represented"
+    } else {
+        return Ok(());
+    };
+    Err(GoldrustError::UnsupportedFormat {
+        format: GoldenFormat::Toml,
+        reason: reason.to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Serialize)]
+    struct Data {
+        name: String,
+        age: u16,
+    }
+
+    #[test]
+    fn write_yaml() {
+        let data = Data {
+            name: "June".to_string(),
+            age: 1,
+        };
+        let mut buf = Vec::new();
+        GoldenFormat::Yaml.write(&mut buf, &data, true).unwrap();
+        assert_eq!(String::from_utf8(buf).unwrap(), "name: June\nage: 1\n");
+    }
+
+    #[test]
+    fn write_json_serialization_error() {
+        let content = std::collections::BTreeMap::from([((1, 2), 3)]);
+        let result = GoldenFormat::Json.write(Vec::new(), &content, true);
+        assert!(matches!(result, Err(GoldrustError::Serialization { .. })));
+    }
+
+    #[test]
+    fn write_toml_unsupported() {
+        let result = GoldenFormat::Toml.write(Vec::new(), &vec![1, 2], true);
+        assert!(matches!(
+            result,
+            Err(GoldrustError::UnsupportedFormat { .. })
+        ));
+    }
+
+    #[test]
+    fn format_from_content_type() {
+        for (content_type, format) in [
+            ("application/json; charset=utf-8", Some(GoldenFormat::Json)),
+            ("application/problem+json", Some(GoldenFormat::Json)),
+            ("text/xml", Some(GoldenFormat::Xml)),
+            ("application/soap+xml", Some(GoldenFormat::Xml)),
+            ("Text/HTML", Some(GoldenFormat::Text)),
+            ("application/octet-stream", None),
+        ] {
+            assert_eq!(GoldenFormat::from_content_type(content_type), format);
+        }
+    }
+
+    #[test]
+    fn xml_round_trip() {
+        #[derive(Serialize, Deserialize, PartialEq, Debug)]
+        struct Envelope {
+            #[serde(rename = "@version")]
+            version: String,
+            name: String,
+            age: u16,
+            tags: Vec<String>,
+        }
+
+        let content = Envelope {
+            version: "1.0".to_string(),
+            name: "June".to_string(),
+            age: 1,
+            tags: vec!["a".to_string(), "b".to_string()],
+        };
+        let mut buf = Vec::new();
+        GoldenFormat::Xml
+            .write(&mut buf, &serde_json::to_value(&content).unwrap(), true)
+            .unwrap();
+        assert_eq!(
+            String::from_utf8(buf.clone()).unwrap(),
+            "<golden version=\"1.0\">\n  <name>June</name>\n  <age>1</age>\n  <tags>a</tags>\n  <tags>b</tags>\n</golden>"
+        );
+        let read: Envelope = GoldenFormat::Xml.read(buf.as_slice()).unwrap();
+        assert_eq!(read, content);
+    }
+
+    #[test]
+    fn message_pack_round_trip() {
+        let content = serde_json::json!({"name": "June", "age": 1, "tags": ["a", null]});
+        let mut buf = Vec::new();
+        GoldenFormat::MessagePack
+            .write(&mut buf, &content, true)
+            .unwrap();
+        let read: serde_json::Value = GoldenFormat::MessagePack.read(buf.as_slice()).unwrap();
+        assert_eq!(read, content);
+    }
+}
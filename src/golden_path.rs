@@ -0,0 +1,142 @@
+use crate::GoldrustError;
+use serde::{Deserialize, Serialize};
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+
+/// The path of a golden file, with helpers for the common ways of accessing it
+///
+/// Dereferences to [`PathBuf`], so it can be used wherever a path is expected.
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Serialize, Deserialize)]
+pub struct GoldenPath {
+    path: PathBuf,
+    /// The extension of the golden file, which can have multiple parts (e.g. `json.gz`)
+    extension: String,
+}
+
+impl GoldenPath {
+    pub(crate) fn new(path: PathBuf, extension: String) -> Self {
+        Self { path, extension }
+    }
+
+    /// Read the golden file as a string, as is
+    ///
+    /// Compressed and content-addressed golden files are not resolved,
+    /// so use [`Goldrust::load_bytes`](crate::Goldrust::load_bytes) for them.
+    pub fn read_string(&self) -> Result<String, GoldrustError> {
+        Ok(std::fs::read_to_string(&self.path)
+            .inspect_err(|_e| tracing::error!(?self.path, "Error reading file"))?)
+    }
+
+    /// Read a JSON golden file as a value
+    ///
+    /// Use [`Goldrust::load_golden`](crate::Goldrust::load_golden) for the other formats.
+    pub fn read_value(&self) -> Result<serde_json::Value, GoldrustError> {
+        Ok(serde_json::from_str(&self.read_string()?)?)
+    }
+
+    /// Whether the golden file exists
+    pub fn exists(&self) -> bool {
+        self.path.exists()
+    }
+
+    /// The path of the named variant of the golden file (e.g. `test-name__404.json`)
+    ///
+    /// This is the same path as [`Goldrust::named_golden_file_path`](crate::Goldrust::named_golden_file_path).
+    pub fn with_variant(&self, name: &str) -> GoldenPath {
+        let file_name = self
+            .path
+            .file_name()
+            .map(|file_name| file_name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let golden_file_name = file_name
+            .strip_suffix(&format!(".{}", self.extension))
+            .unwrap_or(&file_name);
+        GoldenPath::new(
+            self.path
+                .with_file_name(format!("{}__{}.{}", golden_file_name, name, self.extension)),
+            self.extension.clone(),
+        )
+    }
+
+    /// The absolute path of the golden file, which doesn't need to exist
+    pub fn abs(&self) -> Result<PathBuf, GoldrustError> {
+        Ok(std::path::absolute(&self.path)?)
+    }
+}
+
+impl Deref for GoldenPath {
+    type Target = PathBuf;
+
+    fn deref(&self) -> &Self::Target {
+        &self.path
+    }
+}
+
+impl AsRef<Path> for GoldenPath {
+    fn as_ref(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl From<GoldenPath> for PathBuf {
+    fn from(golden_path: GoldenPath) -> Self {
+        golden_path.path
+    }
+}
+
+impl PartialEq<PathBuf> for GoldenPath {
+    fn eq(&self, other: &PathBuf) -> bool {
+        &self.path == other
+    }
+}
+
+impl PartialEq<Path> for GoldenPath {
+    fn eq(&self, other: &Path) -> bool {
+        self.path == other
+    }
+}
+
+impl PartialEq<&Path> for GoldenPath {
+    fn eq(&self, other: &&Path) -> bool {
+        self.path == *other
+    }
+}
+
+impl PartialEq<GoldenPath> for PathBuf {
+    fn eq(&self, other: &GoldenPath) -> bool {
+        self == &other.path
+    }
+}
+
+// Debug as the path, so logs and the display of `Goldrust` show the path as before
+impl std::fmt::Debug for GoldenPath {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.path.fmt(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn golden_path_helpers() {
+        let golden_path = GoldenPath::new(
+            PathBuf::from("tests/resources/golden/base-base.json"),
+            "json".to_string(),
+        );
+        assert!(golden_path.exists());
+        assert_eq!(
+            golden_path.read_value().unwrap(),
+            serde_json::json!({"name": "June", "age": 1})
+        );
+        assert!(golden_path.abs().unwrap().is_absolute());
+
+        let variant = golden_path.with_variant("404");
+        assert_eq!(
+            variant,
+            PathBuf::from("tests/resources/golden/base-base__404.json")
+        );
+        assert!(!variant.exists());
+    }
+}
@@ -0,0 +1,70 @@
+use crate::GoldrustError;
+use serde_json::Value;
+
+/// The top-level keys of a GraphQL response
+const KEYS: [&str; 3] = ["data", "errors", "extensions"];
+
+/// Check that the body is a GraphQL response envelope
+pub(crate) fn validate(body: &Value) -> Result<(), GoldrustError> {
+    let invalid = |reason: &str| {
+        Err(GoldrustError::InvalidGraphQlResponse {
+            reason: reason.to_string(),
+        })
+    };
+    let Some(map) = body.as_object() else {
+        return invalid("The response must be an object");
+    };
+    if !map.contains_key("data") && !map.contains_key("errors") {
+        return invalid("The response must contain `data` or `errors`");
+    }
+    if let Some(key) = map.keys().find(|key| !KEYS.contains(&key.as_str())) {
+        return invalid(&format!("Unexpected top-level key `{}`", key));
+    }
+    if map.get("errors").is_some_and(|errors| !errors.is_array()) {
+        return invalid("`errors` must be an array");
+    }
+    Ok(())
+}
+
+/// Normalize the envelope for stable golden files
+///
+/// An empty `errors` is removed, so it's equivalent to an absent `errors`,
+/// and errors are sorted by path when `sort_errors` is set.
+pub(crate) fn normalize(body: &mut Value, sort_errors: bool) {
+    let Some(map) = body.as_object_mut() else {
+        return;
+    };
+    match map.get_mut("errors") {
+        Some(Value::Array(errors)) if errors.is_empty() => {
+            map.remove("errors");
+        }
+        Some(Value::Array(errors)) if sort_errors => {
+            errors.sort_by_key(|error| error.get("path").map(Value::to_string));
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn validate_envelope() {
+        assert!(validate(&json!({"data": {"user": null}})).is_ok());
+        assert!(validate(&json!({"user": null})).is_err());
+        assert!(validate(&json!({"data": null, "errors": {}})).is_err());
+    }
+
+    #[test]
+    fn normalize_errors() {
+        let mut body = json!({"data": null, "errors": []});
+        normalize(&mut body, true);
+        assert_eq!(body, json!({"data": null}));
+
+        let mut body = json!({"errors": [{"path": ["b"]}, {"path": ["a"]}]});
+        normalize(&mut body, true);
+        assert_eq!(body, json!({"errors": [{"path": ["a"]}, {"path": ["b"]}]}));
+    }
+}
@@ -35,15 +35,60 @@
 //!      - In tests: The mock server uri
 //! 2. Serve the mock response:
 //!    - When a golden file does not exist
-//!       (or an update is required via the `GOLDRUST_UPDATE_GOLDEN_FILES` env var):
+//!      (or an update is required via the `GOLDRUST_UPDATE_GOLDEN_FILES` env var):
 //!       1. Create an external api request
 //!       2. Save the response body to the golden file
 //!    - When a golden file exists and no update is required,
-//!       serve the golden file for mock responses
+//!      serve the golden file for mock responses
 //!
 //! # Async
 //!
-//! Only supports async, as was intended to be used in relevance to http request mocking.
+//! Intended to be used with async http request mocking, through [`Goldrust::run`].
+//! Synchronous clients can use [`Goldrust::run_blocking`] instead,
+//! which doesn't require an async runtime.
+//! Saving is blocking in both cases.
+//!
+//! With the `tokio` feature, [`Goldrust::save_async`] saves golden files
+//! without blocking the runtime,
+//! and [`WarmUp`] records many golden files in one pass with a concurrency limit.
+//!
+//! # reqwest
+//!
+//! With the `reqwest` feature, [`Goldrust::save_reqwest`] records a `reqwest::Response`,
+//! including the status and headers, as a [`GoldenResponse`].
+//!
+//! # gRPC
+//!
+//! With the `grpc` feature, [`Goldrust::save_proto`] saves `prost` messages,
+//! which can be decoded back with [`Goldrust::load_proto`].
+//!
+//! # Mock servers
+//!
+//! With the `wiremock` feature, [`wiremock_template`] creates a `wiremock::ResponseTemplate`
+//! which serves the golden file, including the status and headers saved with
+//! [`Goldrust::save_response`].
+//! For the common single-response case, [`Goldrust::serve`] starts a mock server
+//! serving the golden file, so the client only has to be pointed at its URL.
+//! Similarly, with the `httpmock` feature,
+//! [`httpmock_mock`] registers a mock serving the golden file on an `httpmock::MockServer`.
+//!
+//! Endpoints which only differ by their query parameters can save a golden file
+//! per query string with [`Goldrust::save_query`],
+//! served with [`wiremock_query_template`] or [`httpmock_query_mock`].
+//!
+//! # Features
+//!
+//! Each integration is behind its own optional feature,
+//! so only the integrations in use are compiled.
+//! No features are enabled by default.
+//!
+//! - `tokio`: [`Goldrust::save_async`], timeouts for external calls, and [`WarmUp`]
+//! - `reqwest`: [`Goldrust::save_reqwest`]
+//! - `wiremock`: [`wiremock_template`]
+//! - `httpmock`: [`httpmock_mock`]
+//! - `grpc`: [`Goldrust::save_proto`] and [`Goldrust::load_proto`]
+//! - `jsonschema`: [`Goldrust::assert_schema`]
+//! - `reqwest-middleware`: [`GoldrustMiddleware`], which records and replays transparently
 //!
 //! # Usage
 //!
@@ -57,40 +102,156 @@
 //!
 //! - `GOLDRUST_DIR`: `String`
 //!   - The directory where the golden files will be saved.
-//!   - Defaults to `tests/golden`.
+//!   - Defaults to `tests/resources/golden`.
+//!   - Relative directories are resolved against `CARGO_MANIFEST_DIR`,
+//!     so golden files are found regardless of the current directory.
+//!     Absolute directories are used as is.
+//! - `GOLDRUST_WORKSPACE_ROOT`: `String`
+//!   - The directory which relative `GOLDRUST_DIR`s are resolved against, instead of `CARGO_MANIFEST_DIR`.
+//!   - Use this to share golden files across the crates of a workspace (e.g. `../..`).
+//!     A relative root is resolved against `CARGO_MANIFEST_DIR`.
+//!   - Defaults to no root.
 //! - `GOLDRUST_ALLOW_EXTERNAL_API_CALL`: `bool`
 //!   - Whether external api calls are allowed.
 //!   - Defaults to `false`.
 //! - `GOLDRUST_UPDATE_GOLDEN_FILES`: `bool`
 //!   - Whether golden files should be updated.
 //!   - Defaults to `false`.
+//! - `GOLDRUST_PROFILE`: `String`
+//!   - The profile, which keeps a distinct set of golden files in a subdirectory of `GOLDRUST_DIR`.
+//!   - Defaults to no profile.
+//! - `GOLDRUST_NAME_PREFIX_STRIP`: `String`
+//!   - A prefix which is stripped from golden file names, e.g. common module path segments.
+//!   - Names which collide after stripping are reported as a [`GoldrustError::InvalidConfig`].
+//!   - Defaults to no prefix.
+//! - `GOLDRUST_CRATE_NAME_PREFIX`: `bool`
+//!   - Whether [`goldrust!`] prefixes golden file names with the package name,
+//!     so tests of different crates sharing a `GOLDRUST_DIR` don't collide.
+//!   - Defaults to `false`.
+//! - `GOLDRUST_REVIEW`: `bool`
+//!   - Whether updates are written to pending golden files for [`review`], instead.
+//!   - Defaults to `false`.
+//! - `GOLDRUST_CREATE_MISSING`: `bool`
+//!   - Whether missing golden files are recorded and saved, without updating existing ones.
+//!   - Defaults to `false`.
+//! - `GOLDRUST_FORBID_UPDATE`: `bool`
+//!   - Whether saving returns [`GoldrustError::UpdateForbidden`] instead of writing golden files.
+//!   - Use this in CI, to fail instead of silently recording over golden files.
+//!   - Defaults to `false`.
 //!
 //! Some combinations are invariant and will panic:
 //! (for example, you can't update golden files without allowing external api calls).
+//! Use [`Goldrust::try_new`] to get a [`GoldrustError`] instead of a panic.
+//!
+//! Configurations can also be set explicitly with [`Goldrust::builder`],
+//! in which case the environment variables are only used as defaults,
+//! and invalid combinations are returned as a [`GoldrustError`].
 //!
 //!
+//! # Formats
+//!
+//! Golden files are saved as JSON by default.
+//! Other formats can be chosen with [`GoldenFormat`],
+//! which changes both the serialization backend and the file extension.
+//! For formats which are not built in, implement a [`GoldenCodec`]
+//! and set it with [`GoldrustBuilder::codec`].
+//!
 //! # Current Limitations
 //!
-//! - Content that is to be created as golden files should be JSON serializable, deserializable.
-//!   (This is because the golden files are saved as JSON files)
-//! - Assumes that only a single golden file is required per test.
-//!   (The current implementation creates golden file names based on the thread name of the test)
-//!   If multiple golden files are required, it is recommended to break down the test
-//!   in the current implementation.
-//!   (Having to pass down the golden file name
-//!   and track each seemed like an unnecessary complexity for now)
+//! - Content that is to be created as golden files should be serializable, deserializable.
+//!   (This is because the golden files are saved in serde based formats, such as JSON)
+//! - Golden file names are based on the thread name of the test.
+//!   If multiple golden files are required in a single test,
+//!   use named golden files with [`Goldrust::save_named`],
+//!   which are suffixed with the given name.
 //!
 
+mod blob;
+mod body;
+mod builder;
+mod canonical;
+mod codec;
+mod compare;
+mod compress;
+mod config;
+mod embedded;
+mod error;
+mod format;
+mod golden_path;
+mod graphql;
 mod impl_check;
+mod lock;
+#[cfg(test)]
+mod macro_tests;
+mod metadata;
+#[cfg(feature = "reqwest-middleware")]
+mod middleware;
+#[cfg(any(feature = "wiremock", feature = "httpmock"))]
+mod mock;
+mod normalize;
+mod path;
+mod pipeline;
+mod query;
+mod redact;
+mod response;
+mod retry;
+pub mod review;
+mod stale;
+mod temp;
+mod template;
+mod test_id;
+#[cfg(test)]
+mod test_support;
+#[cfg(feature = "tokio")]
+mod warm_up;
+mod websocket;
 
+pub use blob::{GoldenHasher, Sha256Hasher};
+pub use builder::GoldrustBuilder;
+pub use codec::{CodecError, GoldenCodec, JsonCodec};
+pub use config::GoldrustConfig;
+pub use error::GoldrustError;
+pub use format::GoldenFormat;
+pub use golden_path::GoldenPath;
+pub use metadata::GoldenMetadata;
+#[cfg(feature = "reqwest-middleware")]
+pub use middleware::GoldrustMiddleware;
+#[cfg(feature = "httpmock")]
+pub use mock::{httpmock_mock, httpmock_query_mock};
+#[cfg(feature = "wiremock")]
+pub use mock::{
+    wiremock_query, wiremock_query_template, wiremock_template, ServingMock, WiremockQuery,
+};
+pub use normalize::{normalize_volatile, Normalizer, TIMESTAMP_PLACEHOLDER, UUID_PLACEHOLDER};
+pub use query::normalize_query;
+pub use redact::{Redactable, REDACTED};
+pub use response::GoldenResponse;
+pub use retry::RetryPolicy;
+pub use stale::{assert_no_stale, find_stale};
+pub use temp::clean_temp;
+#[doc(hidden)]
+pub use test_id::__call_site_test_id;
+pub use test_id::__package_test_id;
+pub use test_id::test_id_for;
+#[cfg(feature = "tokio")]
+pub use warm_up::WarmUp;
+pub use websocket::{WsDirection, WsFrame, WsOpcode};
+
+use codec::Codec;
+use compare::{Comparator, FloatTolerance};
 use derive_more::Display;
+use pipeline::SavePipeline;
+use redact::Redaction;
 use serde::{Deserialize, Serialize};
-use std::fs::OpenOptions;
-use std::io::Error;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs::{File, OpenOptions};
+use std::io::Write;
 use std::path::{Path, PathBuf};
+use std::sync::{Arc, PoisonError};
 
 assert_impl_commons_without_default!(Goldrust);
 assert_impl_commons_without_default!(ResponseSource);
+assert_impl_commons!(GoldenFormat);
 
 /// Create a new instance of Goldrust.
 ///
@@ -98,9 +259,33 @@ assert_impl_commons_without_default!(ResponseSource);
 ///
 /// The configurations are based on the environment variables:
 /// - `GOLDRUST_DIR`: The directory where the golden files will be saved.
-///    Defaults to `tests/golden`
+///   Defaults to `tests/golden`
 /// - `GOLDRUST_ALLOW_EXTERNAL_API_CALL`: Whether external api calls are allowed.
 /// - `GOLDRUST_UPDATE_GOLDEN_FILES`: Whether golden files should be updated.
+/// - `GOLDRUST_CRATE_NAME_PREFIX`: Whether the golden file name is prefixed with the package name,
+///   so tests of different crates sharing a `GOLDRUST_DIR` don't collide.
+///   Defaults to `false`, so existing golden files keep their names.
+///
+/// The golden file name is the test id of the enclosing function,
+/// check [`test_id_for`] for the exact transformation.
+/// The id is suffixed with a hash of the call site,
+/// so several calls in one function get distinct golden files.
+///
+/// With `name = ...`, the golden file name is given explicitly instead,
+/// either as format arguments or as an expression evaluating to a string.
+/// This gives distinct golden files within a single test function,
+/// e.g. for parametrized cases driven by a loop:
+///
+/// ```no_run
+/// # use goldrust::{goldrust, Goldrust};
+/// for i in 0..3 {
+///     let goldrust = goldrust!(name = "case_{}", i);
+///     # drop(goldrust);
+/// }
+/// ```
+///
+/// Names which already end with the extension of the format (e.g. `foo.json`)
+/// don't get it appended twice.
 ///
 /// Even if `GOLDRUST_ALLOW_EXTERNAL_API_CALL` is set to `true`,
 /// the default behavior is to use local golden files without making external API calls,
@@ -108,18 +293,60 @@ assert_impl_commons_without_default!(ResponseSource);
 #[macro_export]
 macro_rules! goldrust {
     () => {
-        Goldrust::new({
-            fn f() {}
-            fn type_name_of_val<T>(_: T) -> &'static str {
-                std::any::type_name::<T>()
-            }
-            let mut name = type_name_of_val(f).strip_suffix("::f").unwrap_or("");
-            while let Some(rest) = name.strip_suffix("::{{closure}}") {
-                name = rest;
-            }
-            &name.replace("::", "-")
-        })
+        Goldrust::new(&$crate::__test_id!())
+    };
+    (format = $format:expr) => {
+        Goldrust::new_with_format(&$crate::__test_id!(), $format)
+    };
+    (name = $name:literal $(, $arg:expr)* $(,)?) => {
+        Goldrust::new(&format!($name $(, $arg)*))
+    };
+    (name = $name:expr) => {
+        Goldrust::new(&$name)
+    };
+}
+
+/// Verify or save the actual content against the golden file of the enclosing test
+///
+/// This builds Goldrust the same way as [`goldrust!`],
+/// and calls [`Goldrust::auto_verify`] with the content,
+/// so a single-call test is one line:
+///
+/// ```no_run
+/// # use goldrust::assert_golden;
+/// # fn example(body: serde_json::Value) {
+/// assert_golden!(body);
+/// # }
+/// ```
+///
+/// The format of the golden file can be given as well,
+/// e.g. `assert_golden!(body, format = GoldenFormat::Yaml)`.
+///
+/// # Panics
+///
+/// Panics when the golden file does not match, or can't be saved.
+#[macro_export]
+macro_rules! assert_golden {
+    ($actual:expr $(,)?) => {
+        $crate::__assert_golden($crate::Goldrust::new(&$crate::__test_id!()), $actual)
     };
+    ($actual:expr, format = $format:expr $(,)?) => {
+        $crate::__assert_golden(
+            $crate::Goldrust::new_with_format(&$crate::__test_id!(), $format),
+            $actual,
+        )
+    };
+}
+
+#[doc(hidden)]
+#[track_caller]
+pub fn __assert_golden<T>(mut goldrust: Goldrust, actual: T)
+where
+    T: serde::Serialize + serde::de::DeserializeOwned + PartialEq + std::fmt::Debug,
+{
+    goldrust
+        .auto_verify(actual)
+        .unwrap_or_else(|e| panic!("Failed to save golden file: {}", e));
 }
 
 #[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Debug, Serialize, Deserialize, Display)]
@@ -128,48 +355,474 @@ pub struct Goldrust {
     update_golden_files: bool,
     /// The path to the golden file,
     /// which was automatically generated based on the thread name of the test
-    pub golden_file_path: PathBuf,
+    pub golden_file_path: GoldenPath,
     pub response_source: ResponseSource,
     pub save_check: bool,
+    /// The format the golden file is saved in
+    pub format: GoldenFormat,
+    /// The codec which overrides the format, when set
+    #[serde(skip)]
+    codec: Option<Codec>,
+    /// Field paths which are redacted when saving
+    redactions: Vec<Redaction>,
+    /// Field paths which are ignored when comparing
+    ignored: Vec<String>,
+    /// Field paths of arrays whose order is ignored when comparing
+    unordered: Vec<String>,
+    /// The lowercase names of the response headers which are saved, or every header when unset
+    captured_headers: Option<BTreeSet<String>>,
+    /// The custom comparison used by `verify`
+    #[serde(skip)]
+    comparator: Option<Comparator>,
+    /// The hash naming the blobs of content-addressed golden files
+    #[serde(skip)]
+    hasher: blob::Hasher,
+    /// The temporary directory of an ephemeral instance, removed when dropped
+    #[serde(skip)]
+    ephemeral_dir: Option<temp::EphemeralDir>,
+    /// The tolerance within which floats are considered equal when comparing
+    float_tolerance: Option<FloatTolerance>,
+    /// Normalizers applied when saving and comparing
+    #[serde(skip)]
+    normalizers: Vec<Normalizer>,
+    /// Hooks transforming the content right before it is serialized
+    #[serde(skip)]
+    before_save: Vec<fn(serde_json::Value) -> serde_json::Value>,
+    /// Whether the golden file is pretty-printed
+    pretty: bool,
+    /// Whether golden files are compressed with gzip
+    compress: bool,
+    /// Whether a metadata sidecar file is written when saving
+    metadata: bool,
+    /// Whether golden files are pointers to content-addressed blobs
+    content_addressed: bool,
+    /// Whether recording writes to pending golden files for review
+    review: bool,
+    /// Whether writing golden files is forbidden, e.g. in CI
+    forbid_update: bool,
+    /// Whether golden files are only written when their content changed
+    skip_unchanged: bool,
+    /// Whether the directory of the golden file is created when saving
+    create_dirs: bool,
+    /// Whether object keys are sorted recursively when saving
+    sort_keys: bool,
+    /// The key path which a top-level array is sorted by, when saving and comparing
+    top_level_sort_key: Option<String>,
+    /// Whether JSON embedded in strings is expanded when saving, and restored on read
+    expand_embedded_json: bool,
+    /// Whether golden files are saved in a canonical encoding
+    canonical: bool,
+    /// Whether a newline-delimited JSON line was saved, so the next lines are appended
+    ndjson_started: bool,
+    /// Whether GraphQL errors are sorted by path
+    sort_graphql_errors: bool,
+    /// The retry policy for external API calls
+    retry: Option<RetryPolicy>,
+    /// The base url chosen for the response source
+    base_url: Option<String>,
+    /// The number of external branches which ran
+    external_calls: usize,
+    /// Whether the latency of the external call is saved in the metadata
+    record_latency: bool,
+    /// The latency of the last external call
+    latency: Option<std::time::Duration>,
+    /// The timeout for the external branch of `run`
+    timeout: Option<std::time::Duration>,
+    /// The values substituted into `{{name}}` placeholders when reading golden files
+    template_vars: BTreeMap<String, String>,
+    /// The golden file name, without the extension
+    golden_file_name: String,
+    /// The extension of the golden files, without the leading dot
+    extension: String,
+    /// The directory golden files are written to, with the profile
+    golden_file_dir: PathBuf,
+    /// The directories golden files are read from when `golden_file_dir` doesn't have them
+    fallback_dirs: Vec<PathBuf>,
+    /// Named golden files, and whether each was saved
+    named: BTreeMap<String, bool>,
+    /// The effective configuration
+    config: GoldrustConfig,
+    /// How the response source was decided
+    decision: ResponseDecision,
 }
 
 impl Goldrust {
-    /// Create a new instance of GoldrustBuilder
+    /// Create a new instance of Goldrust
     ///
     /// A new instance of Goldrust should be created for each test.
     ///
     /// Golden file names are based on the thread name of the test.
     /// (e.g. `test::test_name` → `test-test_name.json`)
+    ///
+    /// # Panics
+    ///
+    /// Panics when the configuration is invalid.
+    /// Use [`Goldrust::try_new`] to handle the error instead.
     #[tracing::instrument]
     pub fn new(function_name: &str) -> Self {
-        let golden_file_dir =
-            std::env::var("GOLDRUST_DIR").unwrap_or("tests/resources/golden".to_string());
-        let golden_file_path = Path::new(&golden_file_dir).join(format!("{}.json", function_name));
-
-        let allow_external_api_call: bool = std::env::var("GOLDRUST_ALLOW_EXTERNAL_API_CALL")
-            .unwrap_or("false".to_string())
-            .parse()
-            .expect("GOLDRUST_ALLOW_EXTERNAL_API_CALL must be parseable as a boolean");
-
-        let update_golden_files: bool = std::env::var("GOLDRUST_UPDATE_GOLDEN_FILES")
-            .unwrap_or("false".to_string())
-            .parse()
-            .expect("GOLDRUST_UPDATE_GOLDEN_FILES must be a boolean");
-
-        let save_check = !update_golden_files;
-
-        let response_source = response_source(
-            allow_external_api_call,
-            update_golden_files,
-            golden_file_path.as_ref(),
-        );
+        Self::try_new(function_name).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Try to create a new instance of Goldrust
+    ///
+    /// Same as [`Goldrust::new`], but returns an error instead of panicking
+    /// when an environment variable is not parseable (`GoldrustError::InvalidEnvVar`),
+    /// the configuration is an invalid combination (`GoldrustError::InvalidConfig`),
+    /// or a required golden file does not exist (`GoldrustError::MissingGoldenFile`).
+    #[tracing::instrument]
+    pub fn try_new(function_name: &str) -> Result<Self, GoldrustError> {
+        Self::builder()
+            .golden_file_name(function_name.to_string())
+            .build()
+    }
+
+    /// Create a new instance of Goldrust, with golden files in the given directory
+    ///
+    /// Same as [`Goldrust::new`], but the directory doesn't depend on `GOLDRUST_DIR`,
+    /// so parallel tests can use different directories.
+    ///
+    /// # Panics
+    ///
+    /// Panics when the configuration is invalid.
+    /// Use [`Goldrust::builder`] to handle the error instead.
+    #[tracing::instrument(skip(dir))]
+    pub fn new_in(dir: impl AsRef<Path>, function_name: &str) -> Self {
+        Self::with_name(dir, function_name)
+    }
+
+    /// Create a new instance of Goldrust, saving golden files in the given format
+    ///
+    /// The extension of the golden file follows the format.
+    /// (e.g. `test::test_name` → `test-test_name.yaml` for [`GoldenFormat::Yaml`])
+    #[tracing::instrument]
+    pub fn new_with_format(function_name: &str, format: GoldenFormat) -> Self {
+        Self::builder()
+            .golden_file_name(function_name.to_string())
+            .format(format)
+            .build()
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Create a new instance of Goldrust with an explicit golden file name
+    ///
+    /// Unlike [`goldrust!`], the golden file name is independent of the call site,
+    /// so it survives renaming the test, or running the same logic from a helper.
+    /// This also allows distinct golden files for each case of a parametrized test.
+    ///
+    /// The other configurations are based on the environment variables, same as [`Goldrust::new`].
+    ///
+    /// # Panics
+    ///
+    /// Panics when the configuration is invalid.
+    /// Use [`Goldrust::builder`] to handle the error instead.
+    #[tracing::instrument(skip(dir))]
+    pub fn with_name(dir: impl AsRef<Path>, name: &str) -> Self {
+        Self::builder()
+            .dir(dir.as_ref().to_path_buf())
+            .golden_file_name(name.to_string())
+            .build()
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Create a new instance of Goldrust, with golden files in a temporary directory
+    ///
+    /// The directory is removed when Goldrust is dropped,
+    /// so golden files are never persisted to the repository.
+    /// This is useful to exercise the record and replay loop of Goldrust based code in a unit test.
+    /// External api calls are allowed and golden files are updated,
+    /// regardless of the environment variables.
+    /// Use [`Goldrust::ephemeral_dir`] to find the golden files while debugging.
+    ///
+    /// # Panics
+    ///
+    /// Panics when the temporary directory can't be created.
+    #[tracing::instrument]
+    pub fn ephemeral() -> Self {
+        let dir = tempfile::Builder::new()
+            .prefix("goldrust-")
+            .tempdir()
+            .unwrap_or_else(|e| panic!("Failed to create a temporary directory: {}", e));
+        let mut goldrust = Self::builder()
+            .dir(dir.path().to_path_buf())
+            .allow_external_api_call(true)
+            .update_golden_files(true)
+            .golden_file_name("ephemeral".to_string())
+            .build()
+            .unwrap_or_else(|e| panic!("{}", e));
+        goldrust.ephemeral_dir = Some(temp::EphemeralDir(Arc::new(dir)));
+        goldrust
+    }
+
+    /// The temporary directory of an instance created with [`Goldrust::ephemeral`]
+    pub fn ephemeral_dir(&self) -> Option<&Path> {
+        self.ephemeral_dir.as_ref().map(|dir| dir.0.path())
+    }
+
+    /// Create a new instance of GoldrustBuilder
+    ///
+    /// Use this when configurations should be set explicitly,
+    /// instead of through the environment variables.
+    pub fn builder() -> GoldrustBuilder {
+        GoldrustBuilder::default()
+    }
+
+    /// How the response source was decided, including the inputs of the decision
+    pub fn decision(&self) -> &ResponseDecision {
+        &self.decision
+    }
+
+    /// The effective configuration
+    pub fn config(&self) -> &GoldrustConfig {
+        &self.config
+    }
+
+    /// Whether responses come from the external api, i.e. [`ResponseSource::External`]
+    pub fn is_external(&self) -> bool {
+        self.response_source == ResponseSource::External
+    }
+
+    /// Whether responses come from the golden files, i.e. [`ResponseSource::Local`]
+    pub fn is_local(&self) -> bool {
+        self.response_source == ResponseSource::Local
+    }
+
+    /// Set the base urls, of which one is chosen based on the [`ResponseSource`]
+    ///
+    /// e.g. the mock server uri for `local`, and the actual api for `external`.
+    /// The chosen url is returned by [`Goldrust::base_url`],
+    /// so the request code doesn't have to branch on the response source.
+    pub fn with_base_urls(
+        &mut self,
+        local: impl Into<String>,
+        external: impl Into<String>,
+    ) -> &mut Self {
+        self.base_url = Some(match self.response_source {
+            ResponseSource::Local => local.into(),
+            ResponseSource::External => external.into(),
+        });
+        self
+    }
+
+    /// The base url chosen with [`Goldrust::with_base_urls`], if set
+    pub fn base_url(&self) -> Option<&str> {
+        self.base_url.as_deref()
+    }
+
+    /// Run exactly one of `local` or `external`, based on the [`ResponseSource`]
+    ///
+    /// - [`ResponseSource::Local`]: `local` runs, e.g. to mount the golden file on a mock server.
+    /// - [`ResponseSource::External`]: `external` runs, e.g. to point the client to the actual api.
+    ///
+    /// Returns a closure which saves the content to the golden file.
+    /// Calling it behaves the same as [`Goldrust::save`]:
+    /// the save check is marked as done regardless of the response source,
+    /// while the golden file is only written when golden files should be updated.
+    ///
+    /// Returns [`GoldrustError::ExternalTimeout`] when `external` doesn't finish
+    /// within the timeout set with [`Goldrust::with_timeout`].
+    /// Use [`Goldrust::try_run`] to retry a failing `external` with [`Goldrust::with_retry`].
+    ///
+    /// ```no_run
+    /// # async fn example(goldrust: &mut goldrust::Goldrust) {
+    /// let save = goldrust
+    ///     .run(
+    ///         || async { /* Mount the golden file */ },
+    ///         || async { /* Use the actual api */ },
+    ///     )
+    ///     .await
+    ///     .expect("Failed to run");
+    /// save(&serde_json::json!({"name": "June"})).expect("Failed to save");
+    /// # }
+    /// ```
+    pub async fn run<T, L, LFut, E, EFut>(
+        &mut self,
+        local: L,
+        external: E,
+    ) -> Result<impl FnOnce(&T) -> Result<(), GoldrustError> + '_, GoldrustError>
+    where
+        T: serde::Serialize,
+        L: FnOnce() -> LFut,
+        LFut: std::future::Future<Output = ()>,
+        E: FnOnce() -> EFut,
+        EFut: std::future::Future<Output = ()>,
+    {
+        match self.response_source {
+            ResponseSource::Local => local().await,
+            ResponseSource::External => {
+                self.external_calls += 1;
+                let started = std::time::Instant::now();
+                self.run_external(external()).await?;
+                self.latency = Some(started.elapsed());
+            }
+        }
+        Ok(move |content: &T| self.save(serde_json::to_value(content)?))
+    }
+
+    /// Run exactly one of `local` or a fallible `external`, retrying `external` on errors
+    ///
+    /// Same as [`Goldrust::run`], except that `external` is called again whenever it returns
+    /// an error, with the [`RetryPolicy`] set with [`Goldrust::with_retry`].
+    /// Returns [`GoldrustError::ExternalFailed`] when the last attempt fails,
+    /// and the timeout set with [`Goldrust::with_timeout`] applies to all attempts together.
+    ///
+    /// Requires the `tokio` feature.
+    ///
+    /// ```no_run
+    /// # async fn example(goldrust: &mut goldrust::Goldrust) {
+    /// let save = goldrust
+    ///     .try_run(
+    ///         || async { /* Mount the golden file */ },
+    ///         || async { /* Use the actual api */ Ok::<_, std::io::Error>(()) },
+    ///     )
+    ///     .await
+    ///     .expect("Failed to run");
+    /// save(&serde_json::json!({"name": "June"})).expect("Failed to save");
+    /// # }
+    /// ```
+    #[cfg(feature = "tokio")]
+    pub async fn try_run<T, L, LFut, E, EFut, Err>(
+        &mut self,
+        local: L,
+        mut external: E,
+    ) -> Result<impl FnOnce(&T) -> Result<(), GoldrustError> + '_, GoldrustError>
+    where
+        T: serde::Serialize,
+        L: FnOnce() -> LFut,
+        LFut: std::future::Future<Output = ()>,
+        E: FnMut() -> EFut,
+        EFut: std::future::Future<Output = Result<(), Err>>,
+        Err: std::fmt::Debug + std::fmt::Display,
+    {
+        match self.response_source {
+            ResponseSource::Local => local().await,
+            ResponseSource::External => {
+                self.external_calls += 1;
+                let started = std::time::Instant::now();
+                let mut result = Ok(());
+                self.run_external(async {
+                    result = retry::retry(self.retry, &mut external).await;
+                })
+                .await?;
+                result.map_err(|e| GoldrustError::ExternalFailed {
+                    reason: e.to_string(),
+                })?;
+                self.latency = Some(started.elapsed());
+            }
+        }
+        Ok(move |content: &T| self.save(serde_json::to_value(content)?))
+    }
+
+    /// Run exactly one of `local` or `external`, without an async runtime
+    ///
+    /// Same as [`Goldrust::run`], for synchronous clients such as blocking `reqwest`,
+    /// except that the timeout set with [`Goldrust::with_timeout`] doesn't apply.
+    ///
+    /// ```no_run
+    /// # fn example(goldrust: &mut goldrust::Goldrust) {
+    /// let save = goldrust.run_blocking(
+    ///     || { /* Mount the golden file */ },
+    ///     || { /* Use the actual api */ },
+    /// );
+    /// save(&serde_json::json!({"name": "June"})).expect("Failed to save");
+    /// # }
+    /// ```
+    pub fn run_blocking<T, L, E>(
+        &mut self,
+        local: L,
+        external: E,
+    ) -> impl FnOnce(&T) -> Result<(), GoldrustError> + '_
+    where
+        T: serde::Serialize,
+        L: FnOnce(),
+        E: FnOnce(),
+    {
+        match self.response_source {
+            ResponseSource::Local => local(),
+            ResponseSource::External => {
+                self.external_calls += 1;
+                let started = std::time::Instant::now();
+                external();
+                self.latency = Some(started.elapsed());
+            }
+        }
+        move |content: &T| self.save(serde_json::to_value(content)?)
+    }
+
+    /// Run exactly one of `local` or a fallible `external`, retrying `external` on errors,
+    /// without an async runtime
+    ///
+    /// Same as [`Goldrust::try_run`], for synchronous clients,
+    /// except that the timeout set with [`Goldrust::with_timeout`] doesn't apply.
+    ///
+    /// ```no_run
+    /// # fn example(goldrust: &mut goldrust::Goldrust) {
+    /// let save = goldrust
+    ///     .try_run_blocking(
+    ///         || { /* Mount the golden file */ },
+    ///         || { /* Use the actual api */ Ok::<_, std::io::Error>(()) },
+    ///     )
+    ///     .expect("Failed to run");
+    /// save(&serde_json::json!({"name": "June"})).expect("Failed to save");
+    /// # }
+    /// ```
+    pub fn try_run_blocking<T, L, E, Err>(
+        &mut self,
+        local: L,
+        external: E,
+    ) -> Result<impl FnOnce(&T) -> Result<(), GoldrustError> + '_, GoldrustError>
+    where
+        T: serde::Serialize,
+        L: FnOnce(),
+        E: FnMut() -> Result<(), Err>,
+        Err: std::fmt::Debug + std::fmt::Display,
+    {
+        match self.response_source {
+            ResponseSource::Local => local(),
+            ResponseSource::External => {
+                self.external_calls += 1;
+                let started = std::time::Instant::now();
+                retry::retry_blocking(self.retry, external).map_err(|e| {
+                    GoldrustError::ExternalFailed {
+                        reason: e.to_string(),
+                    }
+                })?;
+                self.latency = Some(started.elapsed());
+            }
+        }
+        Ok(move |content: &T| self.save(serde_json::to_value(content)?))
+    }
 
-        Self {
-            update_golden_files,
-            golden_file_path,
-            response_source,
-            save_check,
+    /// Run the external branch, within the timeout if set
+    async fn run_external<Fut>(&self, external: Fut) -> Result<(), GoldrustError>
+    where
+        Fut: std::future::Future<Output = ()>,
+    {
+        #[cfg(feature = "tokio")]
+        if let Some(timeout) = self.timeout {
+            return tokio::time::timeout(timeout, external)
+                .await
+                .map_err(|_e| GoldrustError::ExternalTimeout { timeout });
         }
+        external.await;
+        Ok(())
+    }
+
+    /// Whether an external branch ran, through [`Goldrust::run`], [`Goldrust::run_blocking`],
+    /// or a request through the `reqwest-middleware` integration
+    ///
+    /// Assert that this is `false` to guard an offline suite against accidental live calls.
+    /// External calls made outside of goldrust can't be detected.
+    pub fn external_call_made(&self) -> bool {
+        self.external_calls > 0
+    }
+
+    /// Disable the save check when Goldrust is dropped
+    ///
+    /// Use this when not saving to the golden files is intentional,
+    /// e.g. when the test returns early.
+    pub fn disarm(&mut self) {
+        self.save_check = true;
+        self.named.values_mut().for_each(|saved| *saved = true);
     }
 
     /// Save content to the golden file
@@ -177,108 +830,3087 @@ impl Goldrust {
     /// This method should be called when required,
     /// or Goldrust will panic when dropped.
     #[tracing::instrument(skip(self, content))]
-    pub fn save<T>(&mut self, content: T) -> Result<(), Error>
+    pub fn save<T>(&mut self, content: T) -> Result<(), GoldrustError>
     where
         T: serde::Serialize,
         for<'de> T: serde::Deserialize<'de>,
         T: std::fmt::Debug,
     {
-        self.save_check = true;
-        if !self.update_golden_files {
-            tracing::debug!("Golden files should not be updated, skipping save");
+        if !self.mark_saved() {
             return Ok(());
         }
-        let file = OpenOptions::new()
-            .write(true)
-            .create(true)
-            .truncate(true)
-            .open(&self.golden_file_path)
-            .inspect_err(|_e| tracing::error!(?self.golden_file_path, "Error opening file"))?;
-        let file_fmt = format!("{:?}", self.golden_file_path);
-
-        serde_json::to_writer_pretty(file, &content)
-            .inspect_err(|_e| tracing::error!(file = file_fmt, "Error writing content to file"))?;
-        tracing::debug!(?self.golden_file_path, "Saved content to golden file");
+        let mut value = serde_json::to_value(&content)
+            .inspect_err(|e| tracing::error!(%e, "Error serializing content"))?;
+        self.prepare(&mut value);
 
-        Ok(())
+        self.write_value(&self.golden_file_path, &value)
     }
-}
-
-/// Evaluates the response source based on the configuration
-///
-/// For detailed combinations of possible evaluations, check the source code.
-#[tracing::instrument]
-fn response_source(
-    allow_external_api_call: bool,
-    update_golden_files: bool,
-    golden_file_path: &Path,
-) -> ResponseSource {
-    let golden_file_exists = golden_file_path.exists();
 
-    let response_source: ResponseSource = match (
-        allow_external_api_call,
-        update_golden_files,
-        golden_file_exists,
-    ) {
-        (false, true, _) => {
-            panic!("Cannot update golden files without allowing external API calls")
+    /// Verify or save the actual content, depending on the response source
+    ///
+    /// With [`ResponseSource::Local`], the golden file is compared with [`Goldrust::verify`].
+    /// With [`ResponseSource::External`], the content is saved with [`Goldrust::save`],
+    /// which only writes when golden files should be updated.
+    /// This replaces the save-or-assert branching of each test with a single call.
+    ///
+    /// # Panics
+    ///
+    /// Panics when the golden file does not match, same as [`Goldrust::verify`].
+    #[track_caller]
+    pub fn auto_verify<T>(&mut self, actual: T) -> Result<(), GoldrustError>
+    where
+        T: serde::Serialize + serde::de::DeserializeOwned + PartialEq + std::fmt::Debug,
+    {
+        match self.response_source {
+            ResponseSource::Local => {
+                self.verify(&actual);
+                Ok(())
+            }
+            ResponseSource::External => self.save(actual),
         }
-        (false, false, false) => {
-            panic!("Cannot test without allowing external API calls when golden files do not exist, create file: {}", golden_file_path.display())
+    }
+
+    /// Save content to the golden file, after letting it redact itself
+    ///
+    /// [`Redactable::redact`] is called on a clone of the content,
+    /// which is then saved the same way as [`Goldrust::save`],
+    /// so the content is left untouched for the rest of the test.
+    #[tracing::instrument(skip(self, content))]
+    pub fn save_redacted<T>(&mut self, content: &T) -> Result<(), GoldrustError>
+    where
+        T: serde::Serialize + Redactable + Clone,
+        for<'de> T: serde::Deserialize<'de>,
+        T: std::fmt::Debug,
+    {
+        let mut redacted = content.clone();
+        redacted.redact();
+        self.save(redacted)
+    }
+
+    /// Save content to the golden file, reporting whether the golden file actually changed
+    ///
+    /// Same as [`Goldrust::save`], but the golden file is only written
+    /// when the content differs from the existing golden file,
+    /// so recording scripts can tell a no-op re-record from real drift.
+    /// Returns [`SaveOutcome::Unchanged`] as well when golden files should not be updated,
+    /// as nothing is written then.
+    /// To fail when a change was expected, assert on the outcome.
+    #[tracing::instrument(skip(self, content))]
+    pub fn save_reporting<T>(&mut self, content: T) -> Result<SaveOutcome, GoldrustError>
+    where
+        T: serde::Serialize,
+        for<'de> T: serde::Deserialize<'de>,
+        T: std::fmt::Debug,
+    {
+        if !self.mark_saved() {
+            return Ok(SaveOutcome::Unchanged);
         }
-        (false, false, true) => {
-            tracing::debug!("Use local golden files without making external API calls");
-            ResponseSource::Local
+        let mut value = serde_json::to_value(&content)
+            .inspect_err(|e| tracing::error!(%e, "Error serializing content"))?;
+        self.prepare(&mut value);
+        let buf = self.serialize_value(&self.golden_file_path, &value)?;
+
+        let outcome = match self.load_file(&self.golden_file_path) {
+            Ok(existing) if existing == buf => SaveOutcome::Unchanged,
+            Ok(_) => SaveOutcome::Updated,
+            Err(_) => SaveOutcome::Created,
+        };
+        if outcome == SaveOutcome::Unchanged {
+            tracing::info!(?self.golden_file_path, "Golden file is unchanged, skipping write");
+        } else {
+            self.write_bytes(&self.golden_file_path, &buf)?;
+            tracing::debug!(?self.golden_file_path, %outcome, "Saved content to golden file");
         }
-        (true, false, false) => {
-            tracing::debug!("Use external API without updating golden files");
-            ResponseSource::External
+        Ok(outcome)
+    }
+
+    /// Save content to the golden file, returning the serialized content
+    ///
+    /// Same as [`Goldrust::save`], but returns the content of the golden file,
+    /// e.g. to feed it into a mock in the same run without reading the golden file back.
+    /// The content is returned before compression,
+    /// and is returned even when golden files should not be updated.
+    #[tracing::instrument(skip(self, content))]
+    pub fn save_returning<T>(&mut self, content: T) -> Result<Vec<u8>, GoldrustError>
+    where
+        T: serde::Serialize,
+        for<'de> T: serde::Deserialize<'de>,
+        T: std::fmt::Debug,
+    {
+        let update = self.mark_saved();
+        let mut value = serde_json::to_value(&content)
+            .inspect_err(|e| tracing::error!(%e, "Error serializing content"))?;
+        self.prepare(&mut value);
+
+        let buf = self.serialize_value(&self.golden_file_path, &value)?;
+        if update {
+            self.write_bytes(&self.golden_file_path, &buf)?;
+            tracing::debug!(?self.golden_file_path, "Saved content to golden file");
         }
-        (true, false, true) => {
-            tracing::debug!("Use local golden files without making external API calls, even though external API calls are allowed");
-            ResponseSource::Local
+        Ok(buf)
+    }
+
+    /// Save content to the golden file, even when golden files should not be updated
+    ///
+    /// Same as [`Goldrust::save`], but ignores `update_golden_files`,
+    /// to regenerate a single golden file from a one-off script
+    /// without running the whole suite in update mode.
+    /// As this bypasses the normal gate, a warning is logged on every call.
+    /// [`GoldrustBuilder::forbid_update`] is still honored.
+    #[tracing::instrument(skip(self, content))]
+    pub fn force_save<T>(&mut self, content: T) -> Result<(), GoldrustError>
+    where
+        T: serde::Serialize,
+        for<'de> T: serde::Deserialize<'de>,
+        T: std::fmt::Debug,
+    {
+        tracing::warn!(
+            ?self.golden_file_path,
+            "Force saving the golden file, bypassing whether golden files should be updated"
+        );
+        self.save_check = true;
+        let mut value = serde_json::to_value(&content)
+            .inspect_err(|e| tracing::error!(%e, "Error serializing content"))?;
+        self.prepare(&mut value);
+
+        self.write_value(&self.golden_file_path, &value)
+    }
+
+    /// Save content to the golden file without blocking the async runtime
+    ///
+    /// Same as [`Goldrust::save`], but serialization and writing run on a blocking thread,
+    /// which keeps the worker threads free for large payloads.
+    ///
+    /// Requires the `tokio` feature.
+    #[cfg(feature = "tokio")]
+    #[tracing::instrument(skip(self, content))]
+    pub async fn save_async<T>(&mut self, content: T) -> Result<(), GoldrustError>
+    where
+        T: serde::Serialize,
+        for<'de> T: serde::Deserialize<'de>,
+        T: std::fmt::Debug + Send + 'static,
+    {
+        if !self.mark_saved() {
+            return Ok(());
         }
-        (true, true, _) => {
-            tracing::debug!("Use external API calls and update golden files");
-            ResponseSource::External
+        let pipeline = self.pipeline();
+        let metadata = self.golden_metadata();
+        let golden_file_path = self.golden_file_path.to_path_buf();
+
+        let saved = tokio::task::spawn_blocking(move || {
+            let mut value = serde_json::to_value(&content)?;
+            pipeline.prepare(&mut value);
+            let buf = pipeline.serialize(&golden_file_path, &value)?;
+            let Some(path) = pipeline.write(&golden_file_path, &buf)? else {
+                return Ok(());
+            };
+            match metadata {
+                Some(metadata) => write_metadata_file(&path, &metadata),
+                None => Ok::<_, GoldrustError>(()),
+            }
+        })
+        .await;
+        match saved {
+            Ok(saved) => saved?,
+            Err(e) if e.is_panic() => std::panic::resume_unwind(e.into_panic()),
+            Err(e) => return Err(std::io::Error::other(e).into()),
         }
-    };
-    response_source
-}
+        tracing::debug!(?self.golden_file_path, "Saved content to golden file");
 
-/// This ensures that the content is saved to the golden file
-/// when an update is required.
-impl Drop for Goldrust {
-    fn drop(&mut self) {
-        if !self.save_check {
-            tracing::error!("Should save item to golden file.\nEven if you've called the `save` methods, it might not be executing due to prior early returns, etc.");
+        Ok(())
+    }
+
+    /// Save a response, including the status and headers, to the golden file
+    ///
+    /// Normalizers and redactions are applied to the response body.
+    ///
+    /// Same as [`Goldrust::save`], this method should be called when required,
+    /// or Goldrust will panic when dropped.
+    #[tracing::instrument(skip(self, response))]
+    pub fn save_response(&mut self, response: &GoldenResponse) -> Result<(), GoldrustError> {
+        if !self.mark_saved() {
+            return Ok(());
         }
+        self.write_response(&self.golden_file_path, response)
     }
-}
 
-#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Debug, Serialize, Deserialize, Display)]
-#[display("{_variant}")]
-pub enum ResponseSource {
-    Local,
-    External,
-}
+    /// Save a [`reqwest::Response`] to the golden file, as a [`GoldenResponse`]
+    ///
+    /// The response is consumed to capture the status, headers and body,
+    /// and the captured response is returned, so the test can go on with the body.
+    /// Bodies which are not valid UTF-8 are stored base64 encoded.
+    ///
+    /// Same as [`Goldrust::save`], this method should be called when required,
+    /// or Goldrust will panic when dropped.
+    ///
+    /// Requires the `reqwest` feature.
+    #[cfg(feature = "reqwest")]
+    #[tracing::instrument(skip(self, response))]
+    pub async fn save_reqwest(
+        &mut self,
+        response: reqwest::Response,
+    ) -> Result<GoldenResponse, GoldrustError> {
+        let response = GoldenResponse::from_reqwest(response).await?;
+        self.save_response(&response)?;
+        Ok(response)
+    }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    /// Save an ordered sequence of responses, such as paginated responses
+    ///
+    /// Each response is saved to a named golden file,
+    /// e.g. `test-name__page0.json`, `test-name__page1.json`, etc.,
+    /// which can be read back in order with [`Goldrust::load_sequence`].
+    ///
+    /// Same as [`Goldrust::save_named`], each name is registered,
+    /// to be checked when Goldrust is dropped.
+    #[tracing::instrument(skip(self, responses))]
+    pub fn save_sequence(&mut self, responses: &[GoldenResponse]) -> Result<(), GoldrustError> {
+        for (index, response) in responses.iter().enumerate() {
+            let name = sequence_name(index);
+            if self.mark_named_saved(&name) {
+                self.write_response(&self.named_golden_file_path(&name), response)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Load the responses saved with [`Goldrust::save_sequence`], in order
+    ///
+    /// Responses are read from `page0` until the first missing page.
+    #[tracing::instrument(skip(self))]
+    pub fn load_sequence(&self) -> Result<Vec<GoldenResponse>, GoldrustError> {
+        let mut responses = Vec::new();
+        loop {
+            let path = self.named_golden_file_path(&sequence_name(responses.len()));
+            if !path.exists() {
+                return Ok(responses);
+            }
+            responses.push(self.read_golden_file(&path)?);
+        }
+    }
+
+    /// Save the response body of a status code variant of the endpoint
+    ///
+    /// Each status is saved to its own named golden file as a [`GoldenResponse`],
+    /// e.g. `test-name__200.json` and `test-name__404.json`,
+    /// so the mock can respond with either variant per request.
+    /// Read it back with [`Goldrust::load_variant`].
+    ///
+    /// Same as [`Goldrust::save_named`], each status is registered,
+    /// to be checked when Goldrust is dropped.
+    #[tracing::instrument(skip(self, body))]
+    pub fn save_variant<T>(&mut self, status: u16, body: &T) -> Result<(), GoldrustError>
+    where
+        T: serde::Serialize,
+    {
+        let name = status.to_string();
+        if !self.mark_named_saved(&name) {
+            return Ok(());
+        }
+        let response = GoldenResponse {
+            status,
+            headers: BTreeMap::new(),
+            body: serde_json::to_value(body)?,
+        };
+        self.write_response(&self.named_golden_file_path(&name), &response)
+    }
+
+    /// Load the status code variant saved with [`Goldrust::save_variant`]
+    #[tracing::instrument(skip(self))]
+    pub fn load_variant(&self, status: u16) -> Result<GoldenResponse, GoldrustError> {
+        self.read_golden_file(&self.named_golden_file_path(&status.to_string()))
+    }
+
+    /// Save content to a named golden file
+    ///
+    /// Use this when a test requires multiple golden files,
+    /// e.g. for sequential API calls.
+    /// The content is saved to [`Goldrust::named_golden_file_path`],
+    /// and the name is registered, so that Goldrust reports an error when dropped
+    /// if a registered name was never saved.
+    #[tracing::instrument(skip(self, content))]
+    pub fn save_named<T>(&mut self, name: &str, content: T) -> Result<(), GoldrustError>
+    where
+        T: serde::Serialize,
+        for<'de> T: serde::Deserialize<'de>,
+        T: std::fmt::Debug,
+    {
+        if !self.mark_named_saved(name) {
+            return Ok(());
+        }
+        let mut value = serde_json::to_value(&content)?;
+        self.prepare(&mut value);
+
+        self.write_value(&self.named_golden_file_path(name), &value)
+    }
+
+    /// The path to a named golden file
+    ///
+    /// Named golden files are suffixed with the name,
+    /// e.g. `first_call` → `test-test_name__first_call.json`.
+    pub fn named_golden_file_path(&self, name: &str) -> PathBuf {
+        named_golden_file_path(
+            &self.golden_file_path,
+            &self.golden_file_name,
+            name,
+            &self.extension,
+        )
+    }
+
+    /// Save content to the named golden file for a query string
+    ///
+    /// Endpoints which only differ by their query parameters get distinct golden files,
+    /// e.g. `?q=rust` → `test-test_name__q=rust.json`.
+    /// The query string is normalized with [`normalize_query`],
+    /// so the order of the parameters doesn't matter.
+    /// Check [`Goldrust::save_named`] for details.
+    #[tracing::instrument(skip(self, content))]
+    pub fn save_query<T>(&mut self, query: &str, content: T) -> Result<(), GoldrustError>
+    where
+        T: serde::Serialize,
+        for<'de> T: serde::Deserialize<'de>,
+        T: std::fmt::Debug,
+    {
+        self.save_named(&query::query_name(query), content)
+    }
+
+    /// The path to the named golden file for a query string
+    ///
+    /// Check [`Goldrust::save_query`] for details.
+    pub fn query_golden_file_path(&self, query: &str) -> PathBuf {
+        self.named_golden_file_path(&query::query_name(query))
+    }
+
+    /// The paths of all golden files the test expects
+    ///
+    /// This is the golden file itself for single-file tests,
+    /// or the named golden files of all registered names.
+    /// Tooling can use this to check the golden files before running the tests.
+    pub fn expected_paths(&self) -> Vec<PathBuf> {
+        if self.named.is_empty() {
+            return vec![self.golden_file_path.to_path_buf()];
+        }
+        self.named
+            .keys()
+            .map(|name| self.named_golden_file_path(name))
+            .collect()
+    }
+
+    /// The path of the golden file for the outgoing request body
+    ///
+    /// e.g. `test-test_name.request.json`.
+    pub fn request_golden_file_path(&self) -> PathBuf {
+        self.golden_file_path.with_file_name(format!(
+            "{}.request.{}",
+            self.golden_file_name, self.extension
+        ))
+    }
+
+    /// Save the outgoing request body to the request golden file
+    ///
+    /// Same as the response, the request golden file is only written
+    /// when golden files should be updated,
+    /// and can be checked afterwards with [`Goldrust::assert_request_matches`].
+    /// As the request golden file accompanies the golden file,
+    /// saving it doesn't count as saving the golden file itself.
+    #[tracing::instrument(skip(self, request))]
+    pub fn save_request<T>(&mut self, request: &T) -> Result<(), GoldrustError>
+    where
+        T: serde::Serialize,
+    {
+        if !self.should_update() {
+            return Ok(());
+        }
+        let mut value = serde_json::to_value(request)?;
+        self.prepare(&mut value);
+
+        self.write_value(&self.request_golden_file_path(), &value)
+    }
+
+    /// Assert that the request golden file matches the outgoing request body
+    ///
+    /// This catches regressions where the client starts sending a different payload.
+    /// The registered normalizers are applied to `actual` before comparing.
+    ///
+    /// # Panics
+    ///
+    /// Panics with the differences,
+    /// or when the request golden file can't be read.
+    #[track_caller]
+    pub fn assert_request_matches<T>(&self, actual: &T)
+    where
+        T: serde::Serialize,
+    {
+        let path = self.request_golden_file_path();
+        let actual = serde_json::to_value(actual)
+            .unwrap_or_else(|e| panic!("Failed to serialize the request: {}", e));
+        let (golden, actual) = self.comparable_file(&path, &actual);
+
+        let differences = compare::diff(&golden, &actual);
+        if !differences.is_empty() {
+            panic!(
+                "Request golden file {} does not match the actual request:\n{}",
+                path.display(),
+                differences.join("\n")
+            );
+        }
+    }
+
+    /// Append a streamed item to the golden file, as a compact JSON line
+    ///
+    /// The first line saved by this instance replaces any previous content,
+    /// and the following lines are appended,
+    /// so newline-delimited JSON streams can be recorded item by item.
+    /// Read back the items with [`Goldrust::load_ndjson`].
+    ///
+    /// The golden file counts as saved once a line is saved.
+    /// Returns [`GoldrustError::InvalidConfig`] for compressed or content-addressed golden files,
+    /// which can't be appended to.
+    #[tracing::instrument(skip(self, value))]
+    pub fn save_ndjson_line<T>(&mut self, value: &T) -> Result<(), GoldrustError>
+    where
+        T: serde::Serialize,
+    {
+        if self.compress {
+            return Err(GoldrustError::InvalidConfig {
+                reason: "Compressed golden files can't be appended to".to_string(),
+            });
+        }
+        if self.content_addressed {
+            return Err(GoldrustError::InvalidConfig {
+                reason: "Content-addressed golden files can't be appended to".to_string(),
+            });
+        }
+        if !self.mark_saved() {
+            return Ok(());
+        }
+        let mut value = serde_json::to_value(value)?;
+        self.prepare(&mut value);
+        let mut line = serde_json::to_vec(&value)?;
+        line.push(b'\n');
+
+        let path = self.write_path(&self.golden_file_path)?;
+        if self.ndjson_started {
+            append_golden_file(&path, &line)
+        } else {
+            self.ndjson_started = true;
+            write_golden_file(&path, &line, false)?;
+            self.write_metadata(&path)
+        }
+    }
+
+    /// Load the items saved with [`Goldrust::save_ndjson_line`] from the golden file, in order
+    #[tracing::instrument(skip(self))]
+    pub fn load_ndjson<T>(&self) -> Result<Vec<T>, GoldrustError>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        let bytes = self.load_bytes()?;
+        let mut items = Vec::new();
+        for line in bytes.split(|&byte| byte == b'\n') {
+            if !line.is_empty() {
+                items.push(serde_json::from_slice(line)?);
+            }
+        }
+        Ok(items)
+    }
+
+    /// Save a GraphQL response to the golden file
+    ///
+    /// Returns [`GoldrustError::InvalidGraphQlResponse`]
+    /// when the body is not a `data`/`errors` envelope.
+    /// An empty `errors` is saved as an absent `errors`,
+    /// and errors are sorted by path with [`Goldrust::sort_graphql_errors`],
+    /// so servers which reorder errors don't produce flaky diffs.
+    ///
+    /// Same as [`Goldrust::save`], this method should be called when required,
+    /// or Goldrust will panic when dropped.
+    #[tracing::instrument(skip(self, body))]
+    pub fn save_graphql(&mut self, body: &serde_json::Value) -> Result<(), GoldrustError> {
+        graphql::validate(body)?;
+        let mut body = body.clone();
+        graphql::normalize(&mut body, self.sort_graphql_errors);
+        self.save(body)
+    }
+
+    /// Assert that the GraphQL golden file matches the actual response
+    ///
+    /// The actual response is normalized the same way as [`Goldrust::save_graphql`],
+    /// so `errors: []` and an absent `errors` are equivalent.
+    ///
+    /// # Panics
+    ///
+    /// Panics with the differences,
+    /// or when the golden file can't be read.
+    #[track_caller]
+    pub fn assert_graphql_matches(&self, actual: &serde_json::Value) {
+        let mut actual = actual.clone();
+        graphql::normalize(&mut actual, self.sort_graphql_errors);
+        self.assert_matches(&actual);
+    }
+
+    /// Set whether GraphQL errors are sorted by path, which defaults to `false`
+    pub fn sort_graphql_errors(&mut self, sort_graphql_errors: bool) -> &mut Self {
+        self.sort_graphql_errors = sort_graphql_errors;
+        self
+    }
+
+    /// Save a transcript of WebSocket frames to the golden file
+    ///
+    /// The frames are saved in order, as a JSON array of `{direction, opcode, payload}`.
+    ///
+    /// Same as [`Goldrust::save`], this method should be called when required,
+    /// or Goldrust will panic when dropped.
+    #[tracing::instrument(skip(self, frames))]
+    pub fn save_transcript(&mut self, frames: &[WsFrame]) -> Result<(), GoldrustError> {
+        self.save(frames.to_vec())
+    }
+
+    /// Load a transcript saved with [`Goldrust::save_transcript`] from the golden file
+    ///
+    /// Use this to replay the received frames in local mode.
+    #[tracing::instrument(skip(self))]
+    pub fn load_transcript(&self) -> Result<Vec<WsFrame>, GoldrustError> {
+        self.read_golden()
+    }
+
+    /// Assert that the transcript golden file matches the actual frames
+    ///
+    /// # Panics
+    ///
+    /// Panics with the differences,
+    /// or when the golden file can't be read.
+    #[track_caller]
+    pub fn assert_transcript_matches(&self, actual: &[WsFrame]) {
+        let actual = serde_json::to_value(actual)
+            .unwrap_or_else(|e| panic!("Failed to serialize the transcript: {}", e));
+        self.assert_matches(&actual);
+    }
+
+    /// Load a response saved with [`Goldrust::save_response`] from the golden file
+    #[tracing::instrument(skip(self))]
+    pub fn load_response(&self) -> Result<GoldenResponse, GoldrustError> {
+        self.read_golden()
+    }
+
+    /// Assert that the golden file is a subset of the actual value
+    ///
+    /// Every key and value present in the golden file should also exist in `actual`,
+    /// while extra keys in `actual` are ignored.
+    /// Arrays should have the same length, and each element is compared as a subset.
+    /// This keeps tests robust to non-breaking additions to the API.
+    ///
+    /// The registered normalizers are applied to `actual` before comparing.
+    ///
+    /// # Panics
+    ///
+    /// Panics with every mismatching path,
+    /// or when the golden file can't be read.
+    #[track_caller]
+    pub fn assert_subset(&self, actual: &serde_json::Value) {
+        let (golden, actual) = self.comparable(actual);
+
+        let mismatches = compare::subset_mismatches(&golden, &actual);
+        if !mismatches.is_empty() {
+            panic!(
+                "Golden file {} is not a subset of the actual value:\n{}",
+                self.golden_file_path.display(),
+                mismatches.join("\n")
+            );
+        }
+    }
+
+    /// Register field paths to be redacted when saving
+    ///
+    /// The values at the given paths are replaced with [`REDACTED`] in `save`,
+    /// leaving the surrounding structure intact,
+    /// so that secrets are not committed to the golden files.
+    ///
+    /// Paths are dot separated object keys, where each key can be followed by an array selector:
+    /// - `access_token`: A top-level field
+    /// - `credentials.client_secret`: A nested field
+    /// - `items[*].token`: A field of every element of an array
+    /// - `items[0].token`: A field of a specific element of an array
+    ///
+    /// Paths which do not exist in the content are ignored.
+    pub fn redact(&mut self, paths: &[&str]) -> &mut Self {
+        self.redactions
+            .extend(paths.iter().map(|path| Redaction::new(path)));
+        self
+    }
+
+    /// Register a field path to be redacted with a custom replacement when saving
+    ///
+    /// Same as [`Goldrust::redact`], but the value is replaced with `replacement`
+    /// instead of [`REDACTED`], so the golden file stays structurally valid,
+    /// e.g. `0` for a numeric field.
+    pub fn redact_with(&mut self, path: &str, replacement: serde_json::Value) -> &mut Self {
+        self.redactions.push(Redaction {
+            path: path.to_string(),
+            replacement,
+        });
+        self
+    }
+
+    /// Register a hook which transforms the content right before it is saved
+    ///
+    /// Hooks run in registration order, after the normalizers and redactions,
+    /// for arbitrary cleanups which don't fit them,
+    /// such as dropping a volatile field or rounding floats.
+    /// Without hooks, the content is saved as is.
+    pub fn before_save(&mut self, hook: fn(serde_json::Value) -> serde_json::Value) -> &mut Self {
+        self.before_save.push(hook);
+        self
+    }
+
+    /// Treat two numbers within `eps` of each other as equal when comparing
+    ///
+    /// This applies recursively to every number in the document,
+    /// for computed floats which differ in the last digits between machines.
+    /// Numbers which are integers on both sides are still compared exactly.
+    ///
+    /// # Panics
+    ///
+    /// Panics when `eps` is negative or not a number.
+    pub fn with_float_tolerance(&mut self, eps: f64) -> &mut Self {
+        assert!(
+            eps >= 0.0,
+            "The float tolerance must be a non-negative number, got {}",
+            eps
+        );
+        self.float_tolerance = Some(FloatTolerance(eps));
+        self
+    }
+
+    /// Set a custom comparison, which replaces the default equality in [`Goldrust::verify`]
+    ///
+    /// The comparison receives the golden and the actual value,
+    /// after normalizing and ignoring paths,
+    /// for domain-specific equality such as set equality of arrays.
+    pub fn compare_with(
+        &mut self,
+        comparator: fn(&serde_json::Value, &serde_json::Value) -> bool,
+    ) -> &mut Self {
+        self.comparator = Some(Comparator(comparator));
+        self
+    }
+
+    /// Register field paths to be ignored when comparing
+    ///
+    /// Unlike [`Goldrust::redact`], the values are kept in the golden file for reference,
+    /// but are replaced with a sentinel on both sides before comparing,
+    /// in [`Goldrust::verify`], [`Goldrust::assert_matches`] and [`Goldrust::assert_subset`].
+    /// Check [`Goldrust::redact`] for the path syntax.
+    pub fn ignore_paths(&mut self, paths: &[&str]) -> &mut Self {
+        self.ignored
+            .extend(paths.iter().map(|path| path.to_string()));
+        self
+    }
+
+    /// Set the allowlist of response headers saved in a [`GoldenResponse`]
+    ///
+    /// Every header is saved by default, which makes golden files noisy
+    /// with headers such as `Date`, and can leak sensitive ones such as `Set-Cookie`.
+    /// With an allowlist, the other headers are dropped before saving.
+    /// Header names are matched case-insensitively.
+    pub fn capture_headers(&mut self, names: &[&str]) -> &mut Self {
+        self.captured_headers = Some(names.iter().map(|name| name.to_ascii_lowercase()).collect());
+        self
+    }
+
+    /// Register paths of arrays whose order is ignored when comparing
+    ///
+    /// The arrays are sorted on both sides before comparing,
+    /// by the canonical serialization of the elements,
+    /// in [`Goldrust::verify`], [`Goldrust::assert_matches`] and [`Goldrust::assert_subset`].
+    /// This is useful for set-like values, such as tags, which come in an arbitrary order.
+    /// Check [`Goldrust::redact`] for the path syntax.
+    pub fn unordered_paths(&mut self, paths: &[&str]) -> &mut Self {
+        self.unordered
+            .extend(paths.iter().map(|path| path.to_string()));
+        self
+    }
+
+    /// Set the hash naming the blobs of content-addressed golden files, which defaults to [`Sha256Hasher`]
+    ///
+    /// Only applies with [`GoldrustBuilder::content_addressed`].
+    /// Check [`GoldenHasher`] for the requirements on the digest.
+    pub fn with_hasher(&mut self, hasher: impl GoldenHasher + 'static) -> &mut Self {
+        self.hasher = blob::Hasher(Arc::new(hasher));
+        self
+    }
+
+    /// Set whether JSON embedded in strings is expanded in golden files, which defaults to `false`
+    ///
+    /// APIs which nest serialized JSON in string fields produce long single-line strings,
+    /// which are hard to review.
+    /// With `true`, strings holding a JSON object or array are saved expanded,
+    /// as `{"$embedded_json": ...}`, and restored into compact JSON strings on read.
+    /// The actual content is compared with embedded JSON in the compact form as well,
+    /// so whitespace in the embedded JSON doesn't produce diffs.
+    pub fn expand_embedded_json(&mut self, expand_embedded_json: bool) -> &mut Self {
+        self.expand_embedded_json = expand_embedded_json;
+        self
+    }
+
+    /// Sort a top-level array by the value at the key path (e.g. `id` or `user.id`)
+    ///
+    /// Responses of list endpoints often come in an arbitrary order,
+    /// which produces huge diffs when golden files are re-recorded.
+    /// The sort is applied when saving, so the stored order is deterministic,
+    /// and to both sides before comparing.
+    /// Numbers are compared numerically, and elements without the key come last.
+    pub fn sort_top_level_by(&mut self, key: &str) -> &mut Self {
+        self.top_level_sort_key = Some(key.to_string());
+        self
+    }
+
+    /// Set whether golden files are only written when their content changed, which defaults to `false`
+    ///
+    /// Saving reads the existing golden file first,
+    /// and skips the write when it already has the same content,
+    /// so a full re-record pass only touches the golden files which actually changed,
+    /// keeping their modification times and the version control status clean.
+    /// Use [`Goldrust::save_reporting`] to find out whether a single save changed the golden file.
+    pub fn skip_unchanged(&mut self, skip_unchanged: bool) -> &mut Self {
+        self.skip_unchanged = skip_unchanged;
+        self
+    }
+
+    /// Set whether the golden file is pretty-printed, which defaults to `true`
+    ///
+    /// With `false`, JSON golden files are saved in the compact representation,
+    /// so the file equals a compact serialization byte-for-byte.
+    pub fn pretty(&mut self, pretty: bool) -> &mut Self {
+        self.pretty = pretty;
+        self
+    }
+
+    /// Set whether a metadata sidecar file is written when saving, which defaults to `false`
+    ///
+    /// The sidecar file (e.g. `test-name.json.meta.json`) holds a [`GoldenMetadata`],
+    /// so reviewers can tell when a golden file was recorded and whether it came from a real call.
+    /// The golden file itself is unchanged, so reading and comparing ignore the metadata.
+    /// Use [`Goldrust::load_metadata`] to read it back.
+    pub fn record_metadata(&mut self, metadata: bool) -> &mut Self {
+        self.metadata = metadata;
+        self
+    }
+
+    /// Set whether the latency of the external call is recorded, which defaults to `false`
+    ///
+    /// The duration of the external branch of [`Goldrust::run`] and [`Goldrust::run_blocking`],
+    /// or of a request through the `reqwest-middleware` integration,
+    /// is saved in the metadata sidecar file, same as [`Goldrust::record_metadata`].
+    /// Mocks built from the golden file, e.g. with [`wiremock_template`],
+    /// then delay the response by the recorded latency,
+    /// which helps testing the timeout handling of the client.
+    pub fn record_latency(&mut self, record_latency: bool) -> &mut Self {
+        self.record_latency = record_latency;
+        self
+    }
+
+    /// Load the metadata of the golden file, saved with [`Goldrust::record_metadata`]
+    pub fn load_metadata(&self) -> Result<GoldenMetadata, GoldrustError> {
+        metadata::read(&self.read_path(&self.golden_file_path))
+    }
+
+    /// Register a value substituted into the `{{name}}` placeholders of golden files on read
+    ///
+    /// Placeholders in the strings of the golden file (e.g. `"now": "{{goldrust_now}}"`)
+    /// are replaced when loading, verifying and serving mocks,
+    /// so time-dependent responses can reflect a fixed clock while the golden file stays stable.
+    /// Saving doesn't add placeholders, so edit them into the golden file by hand.
+    pub fn template_var(&mut self, name: &str, value: impl Into<String>) -> &mut Self {
+        self.template_vars.insert(name.to_string(), value.into());
+        self
+    }
+
+    /// Set whether object keys are sorted recursively when saving, which defaults to `false`
+    ///
+    /// Content with nondeterministic key order, such as a `HashMap`,
+    /// otherwise produces spurious diffs when golden files are re-recorded.
+    pub fn sort_keys(&mut self, sort_keys: bool) -> &mut Self {
+        self.sort_keys = sort_keys;
+        self
+    }
+
+    /// Set whether golden files are saved in a canonical encoding, which defaults to `false`
+    ///
+    /// Canonical golden files are compact, with object keys sorted recursively
+    /// and a deterministic number format (e.g. `1.0` is saved as `1`),
+    /// so the same logical content is saved byte-for-byte identically across machines.
+    /// This takes precedence over [`Goldrust::pretty`].
+    pub fn canonical(&mut self, canonical: bool) -> &mut Self {
+        self.canonical = canonical;
+        self
+    }
+
+    /// Set the retry policy for external API calls made through [`Goldrust::call_external`],
+    /// [`Goldrust::try_run`], or [`Goldrust::try_run_blocking`]
+    ///
+    /// This makes recording resilient to a flaky upstream, such as occasional `503`s.
+    pub fn with_retry(&mut self, policy: RetryPolicy) -> &mut Self {
+        self.retry = Some(policy);
+        self
+    }
+
+    /// Set the timeout for the external branch of [`Goldrust::run`]
+    ///
+    /// This keeps a hung upstream connection from stalling the whole recording pass.
+    ///
+    /// Requires the `tokio` feature.
+    #[cfg(feature = "tokio")]
+    pub fn with_timeout(&mut self, timeout: std::time::Duration) -> &mut Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Make an external API call, retrying with the configured [`RetryPolicy`]
+    ///
+    /// The closure is called again whenever it returns an error,
+    /// until it succeeds or the maximum number of attempts is reached.
+    /// Without a policy, the closure is called exactly once.
+    ///
+    /// Requires the `tokio` feature.
+    #[cfg(feature = "tokio")]
+    pub async fn call_external<F, Fut, T, E>(&self, f: F) -> Result<T, E>
+    where
+        F: FnMut() -> Fut,
+        Fut: std::future::Future<Output = Result<T, E>>,
+        E: std::fmt::Debug,
+    {
+        retry::retry(self.retry, f).await
+    }
+
+    /// Register a normalizer for volatile values
+    ///
+    /// Normalizers are applied in registration order when saving,
+    /// and should be applied to the actual response with [`Goldrust::normalize`] before comparing,
+    /// so that values such as timestamps or request-scoped ids don't produce noisy diffs.
+    ///
+    /// [`normalize_volatile`] covers the common cases of RFC3339 timestamps and UUIDs.
+    pub fn normalize_with(&mut self, normalizer: Normalizer) -> &mut Self {
+        self.normalizers.push(normalizer);
+        self
+    }
+
+    /// Apply the registered normalizers to the value
+    pub fn normalize(&self, value: &mut serde_json::Value) {
+        normalize::apply(value, &self.normalizers);
+    }
+
+    /// Save text to the golden file
+    ///
+    /// The text is written verbatim, for bodies which are not structured,
+    /// such as HTML, error pages or `text/event-stream`.
+    /// Use [`GoldenFormat::Text`] to save these as `.txt` golden files.
+    ///
+    /// Same as [`Goldrust::save`], this method should be called when required,
+    /// or Goldrust will panic when dropped.
+    #[tracing::instrument(skip(self, content))]
+    pub fn save_str(&mut self, content: &str) -> Result<(), GoldrustError> {
+        if self.format != GoldenFormat::Text {
+            tracing::warn!(format = %self.format, "Saving text to a golden file which is not in the text format");
+        }
+        self.save_bytes(content.as_bytes())
+    }
+
+    /// Save a raw body to the golden file, in the JSON golden file pipeline
+    ///
+    /// UTF-8 bodies are saved as a string,
+    /// while other bodies, such as binary payloads,
+    /// are saved base64 encoded as `{"encoding": "base64", "data": "..."}`.
+    /// Read back the raw body with [`Goldrust::load_body`].
+    ///
+    /// Same as [`Goldrust::save`], this method should be called when required,
+    /// or Goldrust will panic when dropped.
+    #[tracing::instrument(skip(self, body))]
+    pub fn save_body(&mut self, body: &[u8]) -> Result<(), GoldrustError> {
+        self.save(body::from_bytes(body))
+    }
+
+    /// Load a raw body saved with [`Goldrust::save_body`] from the golden file
+    ///
+    /// Base64 encoded bodies are decoded transparently.
+    #[tracing::instrument(skip(self))]
+    pub fn load_body(&self) -> Result<Vec<u8>, GoldrustError> {
+        body::to_bytes(&self.read_golden()?)
+    }
+
+    /// Save a raw body to a golden file in the format of its `Content-Type`
+    ///
+    /// The format is chosen with [`GoldenFormat::from_content_type`],
+    /// which also decides the extension (e.g. `test-name.xml` for `application/xml`):
+    /// - JSON bodies are parsed and saved like [`Goldrust::save`],
+    ///   so normalizers, redactions and [`Goldrust::canonical`] are applied.
+    ///   A custom codec doesn't apply, so the golden file stays JSON.
+    /// - Text and XML bodies are saved verbatim.
+    /// - Bodies of unknown types are saved base64 encoded as `{"encoding": "base64", "data": "..."}`,
+    ///   in a JSON golden file.
+    ///
+    /// The golden file is [`Goldrust::content_type_golden_file_path`],
+    /// and can be read back with [`Goldrust::load_with_content_type`].
+    ///
+    /// Same as [`Goldrust::save`], this method should be called when required,
+    /// or Goldrust will panic when dropped.
+    #[tracing::instrument(skip(self, body))]
+    pub fn save_with_content_type(
+        &mut self,
+        body: &[u8],
+        content_type: &str,
+    ) -> Result<(), GoldrustError> {
+        if !self.mark_saved() {
+            return Ok(());
+        }
+        let path = self.content_type_golden_file_path(content_type);
+        match GoldenFormat::from_content_type(content_type) {
+            Some(GoldenFormat::Json) => {
+                let mut value = serde_json::from_slice(body)?;
+                self.prepare(&mut value);
+                let mut buf = Vec::new();
+                GoldenFormat::Json.write(&mut buf, &value, self.pretty && !self.canonical)?;
+                self.write_bytes(&path, &buf)
+            }
+            Some(_) => self.write_bytes(&path, body),
+            None => {
+                let buf = serde_json::to_vec_pretty(&body::to_base64(body))?;
+                self.write_bytes(&path, &buf)
+            }
+        }
+    }
+
+    /// Load a raw body saved with [`Goldrust::save_with_content_type`]
+    ///
+    /// Base64 encoded bodies of unknown types are decoded transparently.
+    #[tracing::instrument(skip(self))]
+    pub fn load_with_content_type(&self, content_type: &str) -> Result<Vec<u8>, GoldrustError> {
+        let bytes = self.load_file(&self.content_type_golden_file_path(content_type))?;
+        match GoldenFormat::from_content_type(content_type) {
+            Some(_) => Ok(bytes),
+            None => body::to_bytes(&serde_json::from_slice(&bytes)?),
+        }
+    }
+
+    /// The path of the golden file for bodies of the `Content-Type`
+    ///
+    /// This is the golden file name with the extension of the format,
+    /// or `json` for unknown types.
+    pub fn content_type_golden_file_path(&self, content_type: &str) -> PathBuf {
+        let extension = GoldenFormat::from_content_type(content_type)
+            .unwrap_or(GoldenFormat::Json)
+            .extension();
+        self.golden_file_path.with_file_name(format!(
+            "{}.{}",
+            self.golden_file_name,
+            golden_file_extension(extension, self.compress)
+        ))
+    }
+
+    /// Assert that the text golden file matches the actual text
+    ///
+    /// Line endings are normalized to `\n` on both sides before comparing,
+    /// so golden files checked out with `\r\n` still match.
+    ///
+    /// # Panics
+    ///
+    /// Panics when the text does not match,
+    /// or when the golden file can't be read.
+    #[track_caller]
+    pub fn assert_text_matches(&self, actual: &str) {
+        let golden = self
+            .load_bytes()
+            .and_then(|bytes| {
+                String::from_utf8(bytes)
+                    .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e).into())
+            })
+            .unwrap_or_else(|e| panic!("Failed to read golden file: {}", e));
+        let golden = golden.replace("\r\n", "\n");
+        let actual = actual.replace("\r\n", "\n");
+        assert_eq!(
+            golden,
+            actual,
+            "Golden file {} does not match the actual text",
+            self.golden_file_path.display()
+        );
+    }
+
+    /// Save raw bytes to the golden file
+    ///
+    /// The bytes are written verbatim, without any serialization,
+    /// which is useful for bodies that are not serde compatible (e.g. protobuf payloads).
+    ///
+    /// Same as [`Goldrust::save`], this method should be called when required,
+    /// or Goldrust will panic when dropped.
+    #[tracing::instrument(skip(self, content))]
+    pub fn save_bytes(&mut self, content: &[u8]) -> Result<(), GoldrustError> {
+        if !self.mark_saved() {
+            return Ok(());
+        }
+        self.write_bytes(&self.golden_file_path, content)?;
+        tracing::debug!(?self.golden_file_path, "Saved bytes to golden file");
+
+        Ok(())
+    }
+
+    /// Assert that the golden file matches the bytes of the reader, without loading either into memory
+    ///
+    /// Both are compared in fixed-size chunks,
+    /// so large bodies saved with [`Goldrust::save_bytes`], such as file downloads,
+    /// can be verified without holding them in memory.
+    /// Compressed golden files are decompressed while reading.
+    ///
+    /// # Panics
+    ///
+    /// Panics with the offset of the first differing byte,
+    /// or when the golden file or the reader can't be read.
+    #[track_caller]
+    pub fn assert_reader_matches(&self, actual: impl std::io::Read) {
+        let difference = self
+            .golden_file_reader(&self.golden_file_path)
+            .and_then(|golden| Ok(compare::first_difference(golden, actual)?))
+            .unwrap_or_else(|e| panic!("Failed to compare golden file: {}", e));
+        if let Some(offset) = difference {
+            panic!(
+                "Golden file {} does not match the actual bytes, first difference at offset {}",
+                self.golden_file_path.display(),
+                offset
+            );
+        }
+    }
+
+    /// Open the golden file for streaming reads, decompressing it when configured
+    fn golden_file_reader(&self, path: &Path) -> Result<Box<dyn std::io::Read>, GoldrustError> {
+        let path = &self.read_path(path);
+        let path = if self.content_addressed {
+            blob::resolve(path, &self.extension)?
+        } else {
+            path.to_path_buf()
+        };
+        let file =
+            File::open(&path).inspect_err(|_e| tracing::error!(?path, "Error opening file"))?;
+        let reader = std::io::BufReader::new(file);
+        if self.compress {
+            Ok(Box::new(flate2::read::GzDecoder::new(reader)))
+        } else {
+            Ok(Box::new(reader))
+        }
+    }
+
+    /// Save a protobuf message to the golden file
+    ///
+    /// The message is written length-delimited,
+    /// and can be read back with [`Goldrust::load_proto`].
+    ///
+    /// Requires the `grpc` feature.
+    #[cfg(feature = "grpc")]
+    #[tracing::instrument(skip(self, message))]
+    pub fn save_proto<M>(&mut self, message: &M) -> Result<(), GoldrustError>
+    where
+        M: prost::Message,
+    {
+        self.save_bytes(&message.encode_length_delimited_to_vec())
+    }
+
+    /// Load a protobuf message saved with [`Goldrust::save_proto`] from the golden file
+    ///
+    /// Requires the `grpc` feature.
+    #[cfg(feature = "grpc")]
+    #[tracing::instrument(skip(self))]
+    pub fn load_proto<M>(&self) -> Result<M, GoldrustError>
+    where
+        M: prost::Message + Default,
+    {
+        Ok(M::decode_length_delimited(self.load_bytes()?.as_slice())?)
+    }
+
+    /// Load the raw bytes of the golden file
+    ///
+    /// Compressed golden files are decompressed transparently,
+    /// so this can be used to build mock responses regardless of the compression.
+    #[tracing::instrument(skip(self))]
+    pub fn load_bytes(&self) -> Result<Vec<u8>, GoldrustError> {
+        self.load_file(&self.golden_file_path)
+    }
+
+    /// Load the raw bytes of a golden file, decompressing them when configured
+    fn load_file(&self, path: &Path) -> Result<Vec<u8>, GoldrustError> {
+        self.pipeline().load(path)
+    }
+
+    /// The path a golden file is read from, looking up the fallback directories
+    fn read_path(&self, path: &Path) -> PathBuf {
+        self.pipeline().read_path(path)
+    }
+
+    /// The options deciding how golden files are read and written
+    fn pipeline(&self) -> SavePipeline {
+        SavePipeline::new(self)
+    }
+
+    /// Mark the golden file as saved, returning whether it should actually be written
+    fn mark_saved(&mut self) -> bool {
+        self.save_check = true;
+        self.should_update()
+    }
+
+    /// Mark the named golden file as saved, returning whether it should actually be written
+    fn mark_named_saved(&mut self, name: &str) -> bool {
+        self.named.insert(name.to_string(), true);
+        self.save_check = true;
+        self.should_update()
+    }
+
+    /// Whether golden files should actually be written
+    fn should_update(&self) -> bool {
+        if !self.update_golden_files {
+            tracing::debug!("Golden files should not be updated, skipping save");
+            return false;
+        }
+        true
+    }
+
+    /// Assert that the golden file matches the actual value
+    ///
+    /// On mismatch, the panic message reports each added (`+`), removed (`-`)
+    /// and changed (`~`) path, instead of dumping both documents.
+    ///
+    /// The registered normalizers are applied to `actual` before comparing.
+    ///
+    /// # Panics
+    ///
+    /// Panics with the differences,
+    /// or when the golden file can't be read.
+    #[track_caller]
+    pub fn assert_matches(&self, actual: &serde_json::Value) {
+        let (golden, actual) = self.comparable(actual);
+
+        let differences = compare::diff(&golden, &actual);
+        if !differences.is_empty() {
+            panic!(
+                "Golden file {} does not match the actual value:\n{}",
+                self.golden_file_path.display(),
+                differences.join("\n")
+            );
+        }
+    }
+
+    /// Assert that the golden file is valid against the JSON Schema
+    ///
+    /// Catches re-records which captured a malformed or unexpected response shape,
+    /// before the golden file is committed.
+    ///
+    /// Requires the `jsonschema` feature.
+    ///
+    /// # Panics
+    ///
+    /// Panics with every violation and its path in the golden file,
+    /// or when the schema is invalid or the golden file can't be read.
+    #[cfg(feature = "jsonschema")]
+    #[track_caller]
+    pub fn assert_schema(&self, schema: &serde_json::Value) {
+        let validator = jsonschema::validator_for(schema)
+            .unwrap_or_else(|e| panic!("Invalid JSON Schema: {}", e));
+        let golden = self
+            .load_golden()
+            .unwrap_or_else(|e| panic!("Failed to load golden file: {}", e));
+
+        let violations: Vec<String> = validator
+            .iter_errors(&golden)
+            .map(|e| match e.instance_path.as_str() {
+                "" => format!("/: {}", e),
+                path => format!("{}: {}", path, e),
+            })
+            .collect();
+        if !violations.is_empty() {
+            panic!(
+                "Golden file {} is not valid against the schema:\n{}",
+                self.golden_file_path.display(),
+                violations.join("\n")
+            );
+        }
+    }
+
+    /// Verify that the golden file matches the actual content
+    ///
+    /// The golden file is deserialized into `T`, and compared with `actual`,
+    /// which removes the boilerplate of reading and comparing the golden file by hand.
+    ///
+    /// The registered normalizers are applied to `actual` before comparing.
+    ///
+    /// # Panics
+    ///
+    /// Panics with the differences when the content does not match,
+    /// or when the golden file can't be read.
+    /// When the golden file no longer deserializes into `T`,
+    /// panics with [`GoldrustError::SchemaMismatch`] instead of a value difference.
+    ///
+    /// XML golden files are deserialized into `T` directly,
+    /// as they don't keep the types of the values.
+    #[track_caller]
+    pub fn verify<T>(&self, actual: &T)
+    where
+        T: serde::Serialize + serde::de::DeserializeOwned + PartialEq + std::fmt::Debug,
+    {
+        let actual = serde_json::to_value(actual)
+            .unwrap_or_else(|e| panic!("Failed to serialize actual content: {}", e));
+        let schema_mismatch = |e: &dyn std::fmt::Display| -> ! {
+            let error = GoldrustError::SchemaMismatch {
+                expected_type: std::any::type_name::<T>(),
+                serde_error: e.to_string(),
+            };
+            panic!("{}: {}", self.golden_file_path.display(), error)
+        };
+        // XML golden files only hold text, so they are deserialized into `T` directly
+        let golden = if self.format == GoldenFormat::Xml && self.codec.is_none() {
+            let golden_content: T = self.read_golden().unwrap_or_else(|e| schema_mismatch(&e));
+            serde_json::to_value(&golden_content)
+                .unwrap_or_else(|e| panic!("Failed to serialize golden content: {}", e))
+        } else {
+            let golden = self
+                .load_golden()
+                .unwrap_or_else(|e| panic!("Failed to read golden file: {}", e));
+            serde_json::from_value::<T>(golden.clone()).unwrap_or_else(|e| schema_mismatch(&e));
+            golden
+        };
+        let (golden, actual) = self.comparable_values(golden, &actual);
+
+        if let Some(comparator) = self.comparator {
+            if !(comparator.0)(&golden, &actual) {
+                panic!(
+                    "Golden file {} does not match the actual content with the custom comparison:\n{}",
+                    self.golden_file_path.display(),
+                    compare::diff(&golden, &actual).join("\n")
+                );
+            }
+            return;
+        }
+        // Ignored paths hold a sentinel, which `T` might not deserialize
+        if !self.ignored.is_empty() {
+            let differences = compare::diff(&golden, &actual);
+            if !differences.is_empty() {
+                panic!(
+                    "Golden file {} does not match the actual content:\n{}",
+                    self.golden_file_path.display(),
+                    differences.join("\n")
+                );
+            }
+            return;
+        }
+        // Both sides are compared after sorting, e.g. with `unordered_paths`
+        let golden_content: T =
+            serde_json::from_value(golden.clone()).unwrap_or_else(|e| schema_mismatch(&e));
+        let actual_content: T = serde_json::from_value(actual.clone())
+            .unwrap_or_else(|e| panic!("Failed to deserialize normalized actual content: {}", e));
+
+        if golden_content != actual_content {
+            panic!(
+                "Golden file {} does not match the actual content:\n{}\n\ngolden: {:?}\nactual: {:?}",
+                self.golden_file_path.display(),
+                compare::diff(&golden, &actual).join("\n"),
+                golden_content,
+                actual_content
+            );
+        }
+    }
+
+    /// Read the golden file, and normalize the actual value, for comparison
+    #[track_caller]
+    fn comparable(&self, actual: &serde_json::Value) -> (serde_json::Value, serde_json::Value) {
+        self.comparable_file(&self.golden_file_path, actual)
+    }
+
+    /// Read the golden file at `path`, and normalize `actual` for comparison
+    fn comparable_file(
+        &self,
+        path: &Path,
+        actual: &serde_json::Value,
+    ) -> (serde_json::Value, serde_json::Value) {
+        let golden = self
+            .load_golden_file(path)
+            .unwrap_or_else(|e| panic!("Failed to read golden file: {}", e));
+        self.comparable_values(golden, actual)
+    }
+
+    /// Normalize `actual`, and replace the ignored paths on both sides, for comparison
+    fn comparable_values(
+        &self,
+        mut golden: serde_json::Value,
+        actual: &serde_json::Value,
+    ) -> (serde_json::Value, serde_json::Value) {
+        let mut actual = actual.clone();
+        self.normalize(&mut actual);
+        if self.expand_embedded_json {
+            embedded::compact(&mut actual);
+        }
+        compare::ignore(&mut golden, &self.ignored);
+        compare::ignore(&mut actual, &self.ignored);
+        compare::sort_unordered(&mut golden, &self.unordered);
+        compare::sort_unordered(&mut actual, &self.unordered);
+        if let Some(key) = &self.top_level_sort_key {
+            compare::sort_top_level_by(&mut golden, key);
+            compare::sort_top_level_by(&mut actual, key);
+        }
+        if let Some(tolerance) = self.float_tolerance {
+            compare::apply_tolerance(&golden, &mut actual, tolerance);
+        }
+        (golden, actual)
+    }
+
+    /// Load the golden file as a value, validating its content
+    ///
+    /// Returns [`GoldrustError::CorruptGolden`] when the golden file is empty or can't be parsed,
+    /// instead of failing later when deserializing the content.
+    pub fn load_golden(&self) -> Result<serde_json::Value, GoldrustError> {
+        self.load_golden_file(&self.golden_file_path)
+    }
+
+    /// Load the golden file at `path` as a value, validating its content
+    fn load_golden_file(&self, path: &Path) -> Result<serde_json::Value, GoldrustError> {
+        let corrupt = |reason: String| GoldrustError::CorruptGolden {
+            path: path.to_path_buf(),
+            reason,
+        };
+        let bytes = self.load_file(path)?;
+        if bytes.iter().all(u8::is_ascii_whitespace) {
+            return Err(corrupt("The golden file is empty".to_string()));
+        }
+        let mut value: serde_json::Value =
+            codec::deserialize(self.format, self.codec.as_ref(), &bytes).map_err(|e| match e {
+                GoldrustError::Serialization { source } => corrupt(source.to_string()),
+                e => e,
+            })?;
+        self.restore(&mut value);
+        Ok(value)
+    }
+
+    /// Read the golden file in the configured format
+    fn read_golden<T>(&self) -> Result<T, GoldrustError>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        self.read_golden_file(&self.golden_file_path)
+    }
+
+    /// Read a golden file in the configured format
+    fn read_golden_file<T>(&self, path: &Path) -> Result<T, GoldrustError>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        let bytes = self.load_file(path)?;
+        if self.template_vars.is_empty() && !self.expand_embedded_json {
+            return codec::deserialize(self.format, self.codec.as_ref(), &bytes);
+        }
+        let mut value = codec::deserialize(self.format, self.codec.as_ref(), &bytes)?;
+        self.restore(&mut value);
+        Ok(serde_json::from_value(value)?)
+    }
+
+    /// Restore the content read from a golden file, substituting the template variables
+    fn restore(&self, value: &mut serde_json::Value) {
+        if self.expand_embedded_json {
+            embedded::restore(value);
+        }
+        template::substitute(value, &self.template_vars);
+    }
+
+    /// Apply normalizers and redactions to the content before it is written
+    fn prepare(&self, value: &mut serde_json::Value) {
+        self.pipeline().prepare(value);
+    }
+
+    /// Write the response to the golden file, applying normalizers and redactions to the body
+    ///
+    /// Only the captured headers are kept, when an allowlist is set.
+    fn write_response(&self, path: &Path, response: &GoldenResponse) -> Result<(), GoldrustError> {
+        let mut response = response.clone();
+        self.prepare(&mut response.body);
+        if let Some(captured) = &self.captured_headers {
+            response
+                .headers
+                .retain(|name, _| captured.contains(&name.to_ascii_lowercase()));
+        }
+
+        self.write_value(path, &serde_json::to_value(&response)?)
+    }
+
+    /// Write the value to the golden file in the configured format
+    ///
+    /// The value is serialized into a buffer before the golden file is opened,
+    /// so a serialization error is reported as [`GoldrustError::Serialization`],
+    /// and leaves the previous golden file intact.
+    /// Only writing the buffer reports [`GoldrustError::Io`].
+    fn write_value(&self, path: &Path, value: &serde_json::Value) -> Result<(), GoldrustError> {
+        let buf = self.serialize_value(path, value)?;
+
+        self.write_bytes(path, &buf)?;
+        tracing::debug!(?path, "Saved content to golden file");
+
+        Ok(())
+    }
+
+    /// Serialize the value into the content of the golden file at `path`
+    fn serialize_value(
+        &self,
+        path: &Path,
+        value: &serde_json::Value,
+    ) -> Result<Vec<u8>, GoldrustError> {
+        self.pipeline().serialize(path, value)
+    }
+
+    /// Write the bytes to the golden file, compressing them when configured
+    fn write_bytes(&self, path: &Path, bytes: &[u8]) -> Result<(), GoldrustError> {
+        match self.pipeline().write(path, bytes)? {
+            Some(path) => self.write_metadata(&path),
+            None => Ok(()),
+        }
+    }
+
+    /// Write the metadata sidecar file of the golden file, when configured
+    fn write_metadata(&self, path: &Path) -> Result<(), GoldrustError> {
+        match self.golden_metadata() {
+            Some(metadata) => write_metadata_file(path, &metadata),
+            None => Ok(()),
+        }
+    }
+
+    /// The metadata of the golden file being saved, when a sidecar file should be written
+    fn golden_metadata(&self) -> Option<GoldenMetadata> {
+        if !self.metadata && !self.record_latency {
+            return None;
+        }
+        let latency = self.latency.filter(|_| self.record_latency);
+        Some(GoldenMetadata::now(self.response_source.clone(), latency))
+    }
+
+    /// The path which is actually written when saving to the golden file
+    ///
+    /// This is the pending golden file when reviewing.
+    /// Returns [`GoldrustError::UpdateForbidden`] when updating golden files is forbidden.
+    fn write_path(&self, path: &Path) -> Result<PathBuf, GoldrustError> {
+        self.pipeline().write_path(path)
+    }
+}
+
+/// Write the metadata sidecar file of the golden file at `path`
+fn write_metadata_file(path: &Path, metadata: &GoldenMetadata) -> Result<(), GoldrustError> {
+    let bytes = serde_json::to_vec_pretty(metadata)?;
+    write_golden_file(&metadata::metadata_path(path), &bytes, false)
+}
+
+/// The extension of the golden files, without the leading dot
+fn golden_file_extension(extension: &str, compress: bool) -> String {
+    if compress {
+        format!("{}.{}", extension, compress::EXTENSION)
+    } else {
+        extension.to_string()
+    }
+}
+
+/// Write the bytes to the golden file, compressing them when required
+///
+/// The golden file is locked while writing,
+/// so that concurrent saves to the same golden file don't interleave.
+/// The bytes are written to a temporary file, which then replaces the golden file,
+/// so the golden file holds either the previous or the new content, never a part of it.
+fn write_golden_file(path: &Path, bytes: &[u8], compress: bool) -> Result<(), GoldrustError> {
+    let compressed;
+    let bytes = if compress {
+        compressed = compress::compress(bytes)?;
+        &compressed
+    } else {
+        bytes
+    };
+
+    let lock = lock::file_lock(path);
+    let _guard = lock.lock().unwrap_or_else(PoisonError::into_inner);
+
+    // Renaming is atomic on the same filesystem, so the golden file is never left half written.
+    // On Windows, `rename` replaces the existing file as well.
+    let temp_file = TempFile(temp_golden_file_path(path));
+    let written = open_golden_file(&temp_file.0)
+        .and_then(|mut file| file.write_all(bytes).and_then(|()| file.sync_all()))
+        .and_then(|()| std::fs::rename(&temp_file.0, path));
+    if let Err(e) = written {
+        tracing::error!(?path, "Error writing to file");
+        return Err(e.into());
+    }
+    std::mem::forget(temp_file);
+    Ok(())
+}
+
+/// A temporary file which is removed when dropped,
+/// so that a write which fails or panics doesn't leave it behind
+struct TempFile(PathBuf);
+
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.0);
+    }
+}
+
+/// The temporary file which is written, before being renamed to the golden file
+fn temp_golden_file_path(path: &Path) -> PathBuf {
+    let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+    path.with_file_name(format!(".{}.{}.tmp", file_name, std::process::id()))
+}
+
+/// Append the bytes to the golden file, creating it when it does not exist
+fn append_golden_file(path: &Path, bytes: &[u8]) -> Result<(), GoldrustError> {
+    let lock = lock::file_lock(path);
+    let _guard = lock.lock().unwrap_or_else(PoisonError::into_inner);
+    OpenOptions::new()
+        .append(true)
+        .create(true)
+        .open(path)
+        .inspect_err(|_e| tracing::error!(?path, "Error opening file"))?
+        .write_all(bytes)
+        .inspect_err(|_e| tracing::error!(?path, "Error writing to file"))?;
+    Ok(())
+}
+
+/// Open the golden file for writing, truncating any previous content
+fn open_golden_file(path: &Path) -> Result<File, std::io::Error> {
+    OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(path)
+        .inspect_err(|_e| tracing::error!(?path, "Error opening file"))
+}
+
+/// The path to a named golden file, next to the golden file
+fn named_golden_file_path(
+    golden_file_path: &Path,
+    golden_file_name: &str,
+    name: &str,
+    extension: &str,
+) -> PathBuf {
+    golden_file_path.with_file_name(format!("{}__{}.{}", golden_file_name, name, extension))
+}
+
+/// The name of the named golden file for a response in a sequence
+fn sequence_name(index: usize) -> String {
+    format!("page{}", index)
+}
+
+/// The existing named golden files of the golden file, sorted
+///
+/// The directory of the golden file and the fallback directories are scanned,
+/// in the same order as [`lookup_path`].
+/// The paths are in the directory of the golden file, so they are resolved with [`lookup_path`].
+fn existing_named_golden_files(
+    golden_file_path: &Path,
+    golden_file_name: &str,
+    extension: &str,
+    dir: &Path,
+    fallback_dirs: &[PathBuf],
+) -> Vec<PathBuf> {
+    let prefix = format!("{}__", golden_file_name);
+    let suffix = format!(".{}", extension);
+    let Some(parent) = golden_file_path.parent() else {
+        return Vec::new();
+    };
+    let fallback_parents = parent
+        .strip_prefix(dir)
+        .map(|relative| {
+            fallback_dirs
+                .iter()
+                .map(|fallback_dir| fallback_dir.join(relative))
+                .collect()
+        })
+        .unwrap_or_else(|_e| Vec::new());
+    let file_names: BTreeSet<String> = std::iter::once(parent.to_path_buf())
+        .chain(fallback_parents)
+        .filter_map(|parent| std::fs::read_dir(parent).ok())
+        .flatten()
+        .filter_map(|entry| entry.ok()?.file_name().into_string().ok())
+        .filter(|file_name| {
+            file_name.starts_with(&prefix)
+                && file_name.ends_with(&suffix)
+                && !file_name.ends_with(metadata::SUFFIX)
+        })
+        .collect();
+    file_names
+        .into_iter()
+        .map(|file_name| golden_file_path.with_file_name(file_name))
+        .collect()
+}
+
+/// Decide the response source based on the configuration and whether the golden files exist
+///
+/// This is the decision [`Goldrust`] makes when it is created,
+/// without touching the filesystem, so the combinations can be tested cheaply:
+///
+/// | allow external | update | exists | result |
+/// |---|---|---|---|
+/// | `false` | `true` | any | [`GoldrustError::InvalidConfig`] |
+/// | `false` | `false` | `false` | [`GoldrustError::MissingGoldenFile`] |
+/// | `false` | `false` | `true` | [`ResponseSource::Local`] |
+/// | `true` | `false` | `false` | [`ResponseSource::External`] |
+/// | `true` | `false` | `true` | [`ResponseSource::Local`] |
+/// | `true` | `true` | any | [`ResponseSource::External`] |
+///
+/// As no path is known here, the path of [`GoldrustError::MissingGoldenFile`] is empty.
+#[tracing::instrument]
+pub fn decide_source(
+    allow_external_api_call: bool,
+    update_golden_files: bool,
+    golden_file_exists: bool,
+) -> Result<ResponseSource, GoldrustError> {
+    match (
+        allow_external_api_call,
+        update_golden_files,
+        golden_file_exists,
+    ) {
+        (false, true, _) => Err(GoldrustError::InvalidConfig {
+            reason: "Cannot update golden files without allowing external API calls".to_string(),
+        }),
+        (false, false, false) => Err(GoldrustError::MissingGoldenFile {
+            path: PathBuf::new(),
+        }),
+        (false, false, true) => {
+            tracing::debug!("Use local golden files without making external API calls");
+            Ok(ResponseSource::Local)
+        }
+        (true, false, false) => {
+            tracing::debug!("Use external API without updating golden files");
+            Ok(ResponseSource::External)
+        }
+        (true, false, true) => {
+            tracing::debug!("Use local golden files without making external API calls, even though external API calls are allowed");
+            Ok(ResponseSource::Local)
+        }
+        (true, true, _) => {
+            tracing::debug!("Use external API calls and update golden files");
+            Ok(ResponseSource::External)
+        }
+    }
+}
+
+/// The path of a golden file in the first directory which has it
+///
+/// Paths outside of `dir`, or missing in every directory, are returned as is.
+fn lookup_path(path: &Path, dir: &Path, fallback_dirs: &[PathBuf]) -> PathBuf {
+    if path.exists() {
+        return path.to_path_buf();
+    }
+    let Ok(relative) = path.strip_prefix(dir) else {
+        return path.to_path_buf();
+    };
+    fallback_dirs
+        .iter()
+        .map(|fallback_dir| fallback_dir.join(relative))
+        .find(|path| path.exists())
+        .unwrap_or_else(|| path.to_path_buf())
+}
+
+/// Evaluates the response source based on the configuration
+///
+/// Check [`decide_source`] for the possible evaluations.
+///
+/// The golden files are considered to exist only when all of the given paths exist.
+#[tracing::instrument]
+fn response_source(
+    allow_external_api_call: bool,
+    update_golden_files: bool,
+    golden_file_paths: &[PathBuf],
+) -> Result<ResponseDecision, GoldrustError> {
+    let missing_golden_file = golden_file_paths.iter().find(|path| !path.exists());
+    let golden_file_exists = missing_golden_file.is_none();
+
+    let response_source = match decide_source(
+        allow_external_api_call,
+        update_golden_files,
+        golden_file_exists,
+    ) {
+        Err(GoldrustError::MissingGoldenFile { .. }) => {
+            return Err(GoldrustError::MissingGoldenFile {
+                path: missing_golden_file.cloned().unwrap_or_default(),
+            })
+        }
+        result => result?,
+    };
+    let decision = ResponseDecision {
+        allow_external_api_call,
+        update_golden_files,
+        golden_file_exists,
+        missing_golden_file: missing_golden_file.cloned(),
+        response_source,
+    };
+    tracing::debug!(?decision, "Decided the response source");
+    Ok(decision)
+}
+
+/// This ensures that the content is saved to the golden file
+/// when an update is required.
+/// Panics when the golden files should have been saved, but weren't,
+/// so forgetting to save fails the test.
+///
+/// Doesn't panic when the thread is already panicking, which would abort the test.
+/// Use [`Goldrust::disarm`] when not saving is intentional.
+impl Drop for Goldrust {
+    fn drop(&mut self) {
+        if std::thread::panicking() {
+            return;
+        }
+        if self.named.is_empty() {
+            if !self.save_check {
+                tracing::error!("Should save item to golden file");
+                panic!("Should save item to golden file.\nEven if you've called the `save` methods, it might not be executing due to prior early returns, etc.");
+            }
+            return;
+        }
+        let unsaved: Vec<&String> = self
+            .named
+            .iter()
+            .filter(|(_, saved)| !**saved)
+            .map(|(name, _)| name)
+            .collect();
+        if !unsaved.is_empty() {
+            tracing::error!(?unsaved, "Should save items to named golden files");
+            panic!("Should save items to named golden files: {:?}\nEven if you've called the `save_named` method, it might not be executing due to prior early returns, etc.", unsaved);
+        }
+    }
+}
+
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Debug, Serialize, Deserialize, Display)]
+#[display("{_variant}")]
+pub enum ResponseSource {
+    Local,
+    External,
+}
+
+/// What saving did to the golden file, returned by [`Goldrust::save_reporting`]
+#[derive(Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Debug, Serialize, Deserialize, Display)]
+#[display("{_variant}")]
+pub enum SaveOutcome {
+    /// The golden file already had the same content, so it wasn't written
+    Unchanged,
+    /// The golden file existed, and was overwritten with different content
+    Updated,
+    /// The golden file didn't exist, and was created
+    Created,
+}
+
+/// The inputs and the outcome of deciding the [`ResponseSource`]
+///
+/// Check [`Goldrust::decision`] to find out why a test used the external api, e.g. in CI.
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Debug, Serialize, Deserialize)]
+pub struct ResponseDecision {
+    pub allow_external_api_call: bool,
+    pub update_golden_files: bool,
+    /// Whether all the required golden files exist
+    pub golden_file_exists: bool,
+    /// The first required golden file which does not exist
+    pub missing_golden_file: Option<PathBuf>,
+    pub response_source: ResponseSource,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::recording;
+
+    #[test]
+    fn decide_source_combinations() {
+        use ResponseSource::*;
+        for (allow, update, exists, expected) in [
+            (false, false, true, Local),
+            (true, false, false, External),
+            (true, false, true, Local),
+            (true, true, false, External),
+            (true, true, true, External),
+        ] {
+            assert_eq!(decide_source(allow, update, exists).unwrap(), expected);
+        }
+        assert!(matches!(
+            decide_source(false, true, true),
+            Err(GoldrustError::InvalidConfig { .. })
+        ));
+        assert!(matches!(
+            decide_source(false, false, false),
+            Err(GoldrustError::MissingGoldenFile { .. })
+        ));
+    }
+
+    #[test]
+    fn macro_with_explicit_name() {
+        let goldrust = goldrust!(name = "base-{}", "base");
+        assert_eq!(
+            goldrust.golden_file_path.file_name().unwrap(),
+            "base-base.json"
+        );
+        let name = String::from("base-base");
+        let goldrust = goldrust!(name = name);
+        assert_eq!(
+            goldrust.golden_file_path.file_name().unwrap(),
+            "base-base.json"
+        );
+    }
+
+    #[test]
+    fn with_name_pins_golden_file_name() {
+        let goldrust = Goldrust::with_name("tests/resources/golden", "base-base");
+        assert_eq!(
+            goldrust.golden_file_path,
+            Path::new("tests/resources/golden").join("base-base.json")
+        );
+    }
+
+    #[test]
+    fn new_in_uses_the_given_dir() {
+        let goldrust = Goldrust::new_in("tests/resources/golden", "base-base");
+        assert_eq!(
+            goldrust.golden_file_path,
+            Path::new("tests/resources/golden").join("base-base.json")
+        );
+    }
+
+    #[test]
+    fn expected_paths() {
+        let goldrust = Goldrust::with_name("tests/resources/golden", "base-base");
+        assert_eq!(
+            goldrust.expected_paths(),
+            vec![goldrust.golden_file_path.clone()]
+        );
+
+        let temp = crate::test_support::temp_dir();
+        let mut goldrust = Goldrust::builder()
+            .dir(temp.path().to_path_buf())
+            .allow_external_api_call(true)
+            .update_golden_files(true)
+            .golden_file_name("goldrust-expected_paths".to_string())
+            .named_golden_files(vec!["first".to_string(), "second".to_string()])
+            .build()
+            .expect("Failed to build");
+        assert_eq!(
+            goldrust.expected_paths(),
+            vec![
+                temp.path().join("goldrust-expected_paths__first.json"),
+                temp.path().join("goldrust-expected_paths__second.json"),
+            ]
+        );
+        goldrust.disarm();
+    }
+
+    #[test]
+    fn decision_records_inputs() {
+        let goldrust = Goldrust::with_name("tests/resources/golden", "base-base");
+        assert_eq!(
+            goldrust.decision(),
+            &ResponseDecision {
+                allow_external_api_call: false,
+                update_golden_files: false,
+                golden_file_exists: true,
+                missing_golden_file: None,
+                response_source: ResponseSource::Local,
+            }
+        );
+    }
+
+    #[test]
+    fn try_new_missing_golden_file() {
+        let result = Goldrust::try_new("goldrust-tests-try_new_missing_golden_file");
+        assert!(matches!(
+            result,
+            Err(GoldrustError::MissingGoldenFile { .. })
+        ));
+    }
+
+    #[test]
+    fn save_redacts_fields() {
+        let mut goldrust = recording("goldrust-save_redacts_fields");
+        goldrust.redact(&["access_token", "items[*].token"]);
+
+        let content = serde_json::json!({
+            "access_token": "secret",
+            "items": [{"token": "secret", "name": "a"}]
+        });
+        goldrust.save(content).expect("Failed to save");
+
+        let saved: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&goldrust.golden_file_path).unwrap())
+                .unwrap();
+        assert_eq!(
+            saved,
+            serde_json::json!({
+                "access_token": REDACTED,
+                "items": [{"token": REDACTED, "name": "a"}]
+            })
+        );
+    }
+
+    #[test]
+    fn save_and_load_response() {
+        let mut goldrust = recording("goldrust-save_and_load_response");
+
+        let response = GoldenResponse {
+            status: 404,
+            headers: [("content-type".to_string(), "application/json".to_string())].into(),
+            body: serde_json::json!({"error": "not found"}),
+        };
+        goldrust.save_response(&response).expect("Failed to save");
+
+        assert_eq!(goldrust.load_response().expect("Failed to load"), response);
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn save_async_writes_content() {
+        let mut goldrust = recording("goldrust-save_async_writes_content");
+
+        let content = serde_json::json!({"name": "June", "age": 1});
+        goldrust
+            .save_async(content.clone())
+            .await
+            .expect("Failed to save");
+
+        let saved: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&goldrust.golden_file_path).unwrap())
+                .unwrap();
+        assert_eq!(saved, content);
+    }
+
+    #[test]
+    fn save_compact() {
+        let mut goldrust = recording("goldrust-save_compact");
+        goldrust.pretty(false);
+
+        goldrust
+            .save(serde_json::json!({"name": "June", "age": 1}))
+            .expect("Failed to save");
+
+        assert_eq!(
+            std::fs::read_to_string(&goldrust.golden_file_path).unwrap(),
+            r#"{"name":"June","age":1}"#
+        );
+    }
+
+    #[test]
+    fn save_named_golden_files() {
+        let mut goldrust = recording("goldrust-save_named_golden_files");
+
+        goldrust
+            .save_named("first_call", serde_json::json!({"page": 1}))
+            .expect("Failed to save");
+        goldrust
+            .save_named("second_call", serde_json::json!({"page": 2}))
+            .expect("Failed to save");
+
+        let second_call_path = goldrust.named_golden_file_path("second_call");
+        assert_eq!(
+            second_call_path,
+            goldrust
+                .golden_file_path
+                .with_file_name("goldrust-save_named_golden_files__second_call.json")
+        );
+        let saved: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(second_call_path).unwrap()).unwrap();
+        assert_eq!(saved, serde_json::json!({"page": 2}));
+    }
+
+    #[test]
+    #[should_panic(expected = "age: expected 1, got 2")]
+    fn assert_subset_mismatch() {
+        let goldrust = Goldrust::with_name("tests/resources/golden", "base-base");
+        goldrust.assert_subset(&serde_json::json!({"name": "June", "age": 2}));
+    }
+
+    #[test]
+    #[should_panic(expected = "~ age: 1 -> 2")]
+    fn assert_matches_mismatch() {
+        let goldrust = Goldrust::with_name("tests/resources/golden", "base-base");
+        goldrust.assert_matches(&serde_json::json!({"name": "June", "age": 2}));
+    }
+
+    #[test]
+    fn save_str_and_assert_text_matches() {
+        let temp = crate::test_support::temp_dir();
+        let mut goldrust = Goldrust::builder()
+            .dir(temp.path().to_path_buf())
+            .allow_external_api_call(true)
+            .update_golden_files(true)
+            .golden_file_name("goldrust-save_str_and_assert_text_matches".to_string())
+            .format(GoldenFormat::Text)
+            .build()
+            .expect("Failed to build");
+
+        goldrust
+            .save_str("<html>\r\n</html>")
+            .expect("Failed to save");
+
+        assert_eq!(goldrust.golden_file_path.extension().unwrap(), "txt");
+        goldrust.assert_text_matches("<html>\n</html>");
+    }
+
+    #[test]
+    fn save_sorts_keys() {
+        let mut goldrust = recording("goldrust-save_sorts_keys");
+        goldrust.pretty(false).sort_keys(true);
+
+        goldrust
+            .save(serde_json::json!({"b": 1, "a": {"d": 2, "c": 3}}))
+            .expect("Failed to save");
+
+        assert_eq!(
+            std::fs::read_to_string(&goldrust.golden_file_path).unwrap(),
+            r#"{"a":{"c":3,"d":2},"b":1}"#
+        );
+    }
+
+    #[test]
+    fn save_compressed() {
+        let temp = crate::test_support::temp_dir();
+        let mut goldrust = Goldrust::builder()
+            .dir(temp.path().to_path_buf())
+            .allow_external_api_call(true)
+            .update_golden_files(true)
+            .golden_file_name("goldrust-save_compressed".to_string())
+            .compress(true)
+            .build()
+            .expect("Failed to build");
+
+        let content = serde_json::json!({"name": "June", "age": 1});
+        goldrust.save(content.clone()).expect("Failed to save");
+
+        assert!(goldrust
+            .golden_file_path
+            .ends_with("goldrust-save_compressed.json.gz"));
+        goldrust.assert_matches(&content);
+    }
+
+    #[test]
+    fn save_pending_for_review() {
+        let temp = crate::test_support::temp_dir();
+        let mut goldrust = Goldrust::builder()
+            .dir(temp.path().to_path_buf())
+            .allow_external_api_call(true)
+            .update_golden_files(true)
+            .golden_file_name("goldrust-save_pending_for_review".to_string())
+            .review(true)
+            .build()
+            .expect("Failed to build");
+
+        goldrust
+            .save(serde_json::json!({"name": "June"}))
+            .expect("Failed to save");
+
+        assert!(temp
+            .path()
+            .join("goldrust-save_pending_for_review.json.new")
+            .exists());
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Data {
+        name: String,
+        age: u16,
+    }
+
+    #[test]
+    fn verify_matches() {
+        let goldrust = Goldrust::with_name("tests/resources/golden", "base-base");
+        goldrust.verify(&Data {
+            name: "June".to_string(),
+            age: 1,
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "~ age: 1 -> 2")]
+    fn verify_mismatch() {
+        let goldrust = Goldrust::with_name("tests/resources/golden", "base-base");
+        goldrust.verify(&Data {
+            name: "June".to_string(),
+            age: 2,
+        });
+    }
+
+    #[tokio::test]
+    async fn run_executes_the_branch_for_the_response_source() {
+        let mut goldrust = recording("goldrust-run_executes_the_branch_for_the_response_source");
+
+        let ran = std::cell::RefCell::new(Vec::new());
+        let save = goldrust
+            .run(
+                || async { ran.borrow_mut().push("local") },
+                || async { ran.borrow_mut().push("external") },
+            )
+            .await
+            .expect("Failed to run");
+        save(&serde_json::json!({"name": "June"})).expect("Failed to save");
+
+        assert_eq!(ran.into_inner(), vec!["external"]);
+        assert!(goldrust.is_external());
+        assert!(!goldrust.is_local());
+        assert!(goldrust.save_check);
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn run_external_timeout() {
+        let mut goldrust = recording("goldrust-run_external_timeout");
+        goldrust.with_timeout(std::time::Duration::from_millis(10));
+
+        let result = goldrust
+            .run::<serde_json::Value, _, _, _, _>(
+                || async {},
+                || tokio::time::sleep(std::time::Duration::from_secs(10)),
+            )
+            .await;
+        assert!(matches!(result, Err(GoldrustError::ExternalTimeout { .. })));
+        drop(result);
+        goldrust.disarm();
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn try_run_retries_failing_external() {
+        let mut goldrust = recording("goldrust-try_run_retries_failing_external");
+        goldrust.with_retry(RetryPolicy {
+            max_attempts: 3,
+            base_delay: std::time::Duration::from_millis(1),
+        });
+
+        let attempts = std::cell::Cell::new(0);
+        let save = goldrust
+            .try_run(
+                || async {},
+                || async {
+                    attempts.set(attempts.get() + 1);
+                    match attempts.get() {
+                        3 => Ok(()),
+                        _ => Err("503"),
+                    }
+                },
+            )
+            .await
+            .expect("Failed to run");
+        save(&serde_json::json!({"name": "June"})).expect("Failed to save");
+
+        assert_eq!(attempts.get(), 3);
+        assert_eq!(goldrust.external_calls, 1);
+    }
+
+    #[test]
+    fn try_run_blocking_fails_after_max_attempts() {
+        let mut goldrust = recording("goldrust-try_run_blocking_fails_after_max_attempts");
+        goldrust.with_retry(RetryPolicy {
+            max_attempts: 2,
+            base_delay: std::time::Duration::from_millis(1),
+        });
+
+        let mut attempts = 0;
+        let result = goldrust.try_run_blocking::<serde_json::Value, _, _, _>(
+            || {},
+            || {
+                attempts += 1;
+                Err("503")
+            },
+        );
+        assert!(matches!(result, Err(GoldrustError::ExternalFailed { .. })));
+        drop(result);
+        assert_eq!(attempts, 2);
+        goldrust.disarm();
+    }
+
+    #[tokio::test]
+    async fn run_local() {
+        let mut goldrust = Goldrust::with_name("tests/resources/golden", "base-base");
+
+        let ran = std::cell::RefCell::new(Vec::new());
+        let save = goldrust
+            .run(
+                || async { ran.borrow_mut().push("local") },
+                || async { ran.borrow_mut().push("external") },
+            )
+            .await
+            .expect("Failed to run");
+        save(&serde_json::json!({"name": "June", "age": 1})).expect("Failed to save");
+
+        assert_eq!(ran.into_inner(), vec!["local"]);
+        assert!(goldrust.is_local());
+        assert!(!goldrust.is_external());
+        assert!(goldrust.save_check);
+    }
+
+    #[cfg(feature = "grpc")]
+    #[test]
+    fn save_and_load_proto() {
+        #[derive(Clone, PartialEq, prost::Message)]
+        struct User {
+            #[prost(string, tag = "1")]
+            name: String,
+            #[prost(uint32, tag = "2")]
+            age: u32,
+        }
+
+        let mut goldrust = recording("goldrust-save_and_load_proto");
+        let user = User {
+            name: "June".to_string(),
+            age: 1,
+        };
+        goldrust.save_proto(&user).expect("Failed to save");
+
+        assert_eq!(goldrust.load_proto::<User>().expect("Failed to load"), user);
+    }
+
+    #[test]
+    fn save_and_assert_request() {
+        let mut goldrust = recording("goldrust-save_and_assert_request");
+        let request = serde_json::json!({"name": "June", "age": 1});
+        goldrust.save_request(&request).expect("Failed to save");
+
+        assert_eq!(
+            goldrust.request_golden_file_path(),
+            goldrust
+                .golden_file_path
+                .with_file_name("goldrust-save_and_assert_request.request.json")
+        );
+        goldrust.assert_request_matches(&request);
+        goldrust.save(request).expect("Failed to save");
+    }
+
+    #[test]
+    #[should_panic(expected = "~ age: 1 -> 2")]
+    fn assert_request_matches_mismatch() {
+        let mut goldrust = recording("goldrust-assert_request_matches_mismatch");
+        goldrust
+            .save_request(&serde_json::json!({"age": 1}))
+            .expect("Failed to save");
+        goldrust.save_check = true;
+
+        goldrust.assert_request_matches(&serde_json::json!({"age": 2}));
+    }
+
+    #[test]
+    #[should_panic(expected = "re-record the golden file: missing field `email`")]
+    fn verify_schema_mismatch() {
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        struct User {
+            name: String,
+            email: String,
+        }
+
+        let goldrust = Goldrust::with_name("tests/resources/golden", "base-base");
+        goldrust.verify(&User {
+            name: "June".to_string(),
+            email: "june@example.com".to_string(),
+        });
+    }
 
     #[test]
-    fn display_goldrust() {
-        let goldrust = goldrust!();
+    fn save_update_forbidden() {
+        let temp = crate::test_support::temp_dir();
+        let mut goldrust = Goldrust::builder()
+            .dir(temp.path().to_path_buf())
+            .allow_external_api_call(true)
+            .update_golden_files(true)
+            .forbid_update(true)
+            .golden_file_name("goldrust-save_update_forbidden".to_string())
+            .build()
+            .expect("Failed to build");
+
+        let result = goldrust.save(serde_json::json!({"name": "June"}));
+        assert!(matches!(result, Err(GoldrustError::UpdateForbidden { .. })));
+        assert!(!goldrust.golden_file_path.exists());
+    }
+
+    #[test]
+    #[should_panic(expected = "Should save item to golden file")]
+    fn drop_without_save_panics() {
+        recording("goldrust-drop_without_save_panics");
+    }
+
+    #[test]
+    fn drop_disarmed_without_save() {
+        let mut goldrust = recording("goldrust-drop_disarmed_without_save");
+        goldrust.disarm();
+    }
+
+    #[test]
+    fn save_canonical() {
+        let mut goldrust = recording("goldrust-save_canonical");
+        goldrust.canonical(true);
+        goldrust
+            .save(serde_json::json!({"b": 1.0, "a": [2.5]}))
+            .expect("Failed to save");
+
         assert_eq!(
-            format!("{}", goldrust),
-            format!(
-                "{}, {:?}, {}, {}",
-                goldrust.update_golden_files,
-                goldrust.golden_file_path,
-                goldrust.response_source,
-                goldrust.save_check
+            std::fs::read_to_string(&goldrust.golden_file_path).unwrap(),
+            r#"{"a":[2.5],"b":1}"#
+        );
+    }
+
+    #[cfg(feature = "reqwest")]
+    #[tokio::test]
+    async fn save_reqwest() {
+        let mock_server = wiremock::MockServer::start().await;
+        wiremock::Mock::given(wiremock::matchers::path("/"))
+            .respond_with(
+                wiremock::ResponseTemplate::new(201)
+                    .insert_header("x-request-id", "1")
+                    .set_body_bytes(vec![0xff, 0x00]),
             )
+            .mount(&mock_server)
+            .await;
+        let response = reqwest::get(mock_server.uri())
+            .await
+            .expect("Failed to send");
+
+        let mut goldrust = recording("goldrust-save_reqwest");
+        let saved = goldrust
+            .save_reqwest(response)
+            .await
+            .expect("Failed to save");
+
+        assert_eq!(saved.status, 201);
+        assert_eq!(saved.headers["x-request-id"], "1");
+        assert_eq!(
+            saved.body,
+            serde_json::json!({"encoding": "base64", "data": "/wA="})
+        );
+        assert_eq!(goldrust.load_response().expect("Failed to load"), saved);
+    }
+
+    #[test]
+    fn save_and_load_binary_body() {
+        let mut goldrust = recording("goldrust-save_and_load_binary_body");
+        goldrust
+            .save_body(&[0x1f, 0x8b, 0xff])
+            .expect("Failed to save");
+
+        assert_eq!(
+            goldrust.load_body().expect("Failed to load"),
+            vec![0x1f, 0x8b, 0xff]
+        );
+    }
+
+    #[test]
+    fn verify_ignore_paths() {
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        struct Data {
+            name: String,
+            age: u16,
+        }
+
+        let mut goldrust = Goldrust::with_name("tests/resources/golden", "base-base");
+        goldrust.ignore_paths(&["age"]);
+
+        let actual = Data {
+            name: "June".to_string(),
+            age: 2,
+        };
+        goldrust.verify(&actual);
+        goldrust.assert_matches(&serde_json::to_value(&actual).unwrap());
+    }
+
+    #[test]
+    fn save_and_load_transcript() {
+        let mut goldrust = recording("goldrust-save_and_load_transcript");
+        let frames = vec![
+            WsFrame::text(WsDirection::Sent, r#"{"subscribe":"ticker"}"#),
+            WsFrame::binary(WsDirection::Received, &[0x01, 0xff]),
+        ];
+        goldrust.save_transcript(&frames).expect("Failed to save");
+
+        assert_eq!(goldrust.load_transcript().expect("Failed to load"), frames);
+        goldrust.assert_transcript_matches(&frames);
+    }
+
+    #[test]
+    fn save_before_save_hook() {
+        let mut goldrust = recording("goldrust-save_before_save_hook");
+        goldrust.before_save(|mut value| {
+            value.as_object_mut().unwrap().remove("server");
+            value
+        });
+        goldrust
+            .save(serde_json::json!({"name": "June", "server": "nginx"}))
+            .expect("Failed to save");
+
+        goldrust.assert_matches(&serde_json::json!({"name": "June"}));
+    }
+
+    #[test]
+    fn save_graphql() {
+        let mut goldrust = recording("goldrust-save_graphql");
+        goldrust.sort_graphql_errors(true);
+        goldrust
+            .save_graphql(&serde_json::json!({
+                "data": null,
+                "errors": [{"path": ["b"]}, {"path": ["a"]}]
+            }))
+            .expect("Failed to save");
+
+        goldrust.assert_graphql_matches(&serde_json::json!({
+            "data": null,
+            "errors": [{"path": ["a"]}, {"path": ["b"]}]
+        }));
+    }
+
+    #[test]
+    fn save_graphql_invalid_envelope() {
+        let mut goldrust = recording("goldrust-save_graphql_invalid_envelope");
+        let result = goldrust.save_graphql(&serde_json::json!([]));
+        assert!(matches!(
+            result,
+            Err(GoldrustError::InvalidGraphQlResponse { .. })
+        ));
+        goldrust.disarm();
+    }
+
+    #[test]
+    fn base_url_follows_response_source() {
+        let mut goldrust = Goldrust::with_name("tests/resources/golden", "base-base");
+        assert_eq!(goldrust.base_url(), None);
+
+        goldrust.with_base_urls("http://127.0.0.1:8080", "https://api.example.com");
+        assert_eq!(goldrust.base_url(), Some("http://127.0.0.1:8080"));
+    }
+
+    #[test]
+    fn save_and_load_ndjson() {
+        let mut goldrust = recording("goldrust-save_and_load_ndjson");
+        for id in 0..3 {
+            goldrust
+                .save_ndjson_line(&serde_json::json!({"id": id}))
+                .expect("Failed to save");
+        }
+
+        assert_eq!(
+            std::fs::read_to_string(&goldrust.golden_file_path).unwrap(),
+            "{\"id\":0}\n{\"id\":1}\n{\"id\":2}\n"
+        );
+        let items: Vec<serde_json::Value> = goldrust.load_ndjson().expect("Failed to load");
+        assert_eq!(items.len(), 3);
+    }
+
+    #[test]
+    fn run_blocking_executes_the_branch_for_the_response_source() {
+        let mut goldrust =
+            recording("goldrust-run_blocking_executes_the_branch_for_the_response_source");
+
+        let ran = std::cell::RefCell::new(Vec::new());
+        let save = goldrust.run_blocking(
+            || ran.borrow_mut().push("local"),
+            || ran.borrow_mut().push("external"),
+        );
+        save(&serde_json::json!({"name": "June"})).expect("Failed to save");
+
+        assert_eq!(ran.into_inner(), vec!["external"]);
+    }
+
+    #[test]
+    fn save_serialization_error_does_not_write() {
+        let mut goldrust = recording("goldrust-save_serialization_error_does_not_write");
+        let _ = std::fs::remove_file(&goldrust.golden_file_path);
+
+        let content = BTreeMap::from([((1, 2), 3)]);
+        let result = goldrust.save(content);
+        assert!(matches!(result, Err(GoldrustError::Serialization { .. })));
+        assert!(!goldrust.golden_file_path.exists());
+    }
+
+    #[test]
+    fn verify_compare_with() {
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        struct Data {
+            name: String,
+            age: u16,
+        }
+
+        let mut goldrust = Goldrust::with_name("tests/resources/golden", "base-base");
+        goldrust.compare_with(|golden, actual| golden["name"] == actual["name"]);
+        goldrust.verify(&Data {
+            name: "June".to_string(),
+            age: 2,
+        });
+    }
+
+    #[test]
+    fn save_replaces_the_golden_file() {
+        let mut goldrust = recording("goldrust-save_replaces_the_golden_file");
+        std::fs::write(
+            &goldrust.golden_file_path,
+            "previous content, which is longer",
+        )
+        .unwrap();
+        goldrust.pretty(false);
+        goldrust
+            .save(serde_json::json!({"name": "June"}))
+            .expect("Failed to save");
+
+        assert_eq!(
+            std::fs::read_to_string(&goldrust.golden_file_path).unwrap(),
+            r#"{"name":"June"}"#
+        );
+        assert!(!temp_golden_file_path(&goldrust.golden_file_path).exists());
+    }
+
+    #[test]
+    fn save_and_load_variants() {
+        let mut goldrust = recording("goldrust-save_and_load_variants");
+        goldrust
+            .save_variant(200, &serde_json::json!({"name": "June"}))
+            .expect("Failed to save");
+        goldrust
+            .save_variant(404, &serde_json::json!({"error": "not found"}))
+            .expect("Failed to save");
+
+        assert!(goldrust.named_golden_file_path("404").exists());
+        let not_found = goldrust.load_variant(404).expect("Failed to load");
+        assert_eq!(not_found.status, 404);
+        assert_eq!(not_found.body, serde_json::json!({"error": "not found"}));
+    }
+
+    #[test]
+    fn save_with_content_type_dispatches_format() {
+        let mut goldrust = recording("goldrust-save_with_content_type_dispatches_format");
+        let xml = b"<user><name>June</name></user>";
+        goldrust
+            .save_with_content_type(xml, "application/xml; charset=utf-8")
+            .expect("Failed to save");
+        let binary = [0u8, 159, 146, 150];
+        goldrust
+            .save_with_content_type(&binary, "application/octet-stream")
+            .expect("Failed to save");
+
+        let xml_path = goldrust.content_type_golden_file_path("text/xml");
+        assert_eq!(xml_path.extension().unwrap(), "xml");
+        assert_eq!(std::fs::read(xml_path).unwrap(), xml);
+        assert_eq!(
+            goldrust
+                .load_with_content_type("application/octet-stream")
+                .unwrap(),
+            binary
+        );
+    }
+
+    #[test]
+    fn save_with_content_type_canonical_json() {
+        let mut goldrust = recording("goldrust-save_with_content_type_canonical_json");
+        goldrust.canonical(true);
+        goldrust
+            .save_with_content_type(br#"{"b": 1, "a": 2.0}"#, "application/json")
+            .expect("Failed to save");
+
+        assert_eq!(
+            goldrust.load_with_content_type("application/json").unwrap(),
+            br#"{"a":2,"b":1}"#
+        );
+    }
+
+    #[test]
+    fn save_with_content_type_json_ignores_codec() {
+        let temp = crate::test_support::temp_dir();
+        let mut goldrust = Goldrust::builder()
+            .dir(temp.path().to_path_buf())
+            .allow_external_api_call(true)
+            .update_golden_files(true)
+            .golden_file_name("golden".to_string())
+            .codec(JsonCodec)
+            .build()
+            .expect("Failed to build");
+        goldrust.pretty(false);
+        goldrust
+            .save_with_content_type(br#"{"name": "June"}"#, "application/json")
+            .expect("Failed to save");
+
+        assert_eq!(
+            goldrust.load_with_content_type("application/json").unwrap(),
+            br#"{"name":"June"}"#
+        );
+    }
+
+    #[test]
+    fn load_golden_detects_corrupt_golden_file() {
+        let temp = crate::test_support::temp_dir();
+        let dir = temp.path();
+        let load = |content: &str| {
+            std::fs::write(dir.join("golden.json"), content).unwrap();
+            Goldrust::with_name(dir, "golden").load_golden()
+        };
+
+        assert!(matches!(load(""), Err(GoldrustError::CorruptGolden { .. })));
+        assert!(matches!(
+            load(r#"{"name": "#),
+            Err(GoldrustError::CorruptGolden { .. })
+        ));
+        assert_eq!(
+            load(r#"{"name": "June"}"#).unwrap(),
+            serde_json::json!({"name": "June"})
+        );
+    }
+
+    #[test]
+    fn assert_reader_matches_large_bytes() {
+        let temp = crate::test_support::temp_dir();
+        let mut goldrust = Goldrust::builder()
+            .dir(temp.path().to_path_buf())
+            .allow_external_api_call(true)
+            .update_golden_files(true)
+            .golden_file_name("goldrust-assert_reader_matches_large_bytes".to_string())
+            .compress(true)
+            .build()
+            .expect("Failed to build");
+        let bytes: Vec<u8> = (0..200_000u32).map(|i| (i % 251) as u8).collect();
+        goldrust.save_bytes(&bytes).expect("Failed to save");
+
+        goldrust.assert_reader_matches(bytes.as_slice());
+        let mut changed = bytes.clone();
+        changed[150_000] ^= 1;
+        let mismatch = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            goldrust.assert_reader_matches(changed.as_slice())
+        }));
+        assert!(mismatch.is_err());
+    }
+
+    #[test]
+    fn auto_verify_saves_then_verifies() {
+        let temp = crate::test_support::temp_dir();
+        let dir = temp.path();
+        let build = |allow_external_api_call: bool| {
+            Goldrust::builder()
+                .dir(dir.to_path_buf())
+                .allow_external_api_call(allow_external_api_call)
+                .update_golden_files(allow_external_api_call)
+                .golden_file_name("golden".to_string())
+                .build()
+                .expect("Failed to build")
+        };
+        std::fs::create_dir_all(dir).unwrap();
+        let content = serde_json::json!({"name": "June"});
+
+        let mut recording = build(true);
+        recording
+            .auto_verify(content.clone())
+            .expect("Failed to save");
+        drop(recording);
+
+        let mut local = build(false);
+        assert_eq!(local.response_source, ResponseSource::Local);
+        local.auto_verify(content).expect("Failed to verify");
+        let mismatch = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            local.auto_verify(serde_json::json!({"name": "Bob"}))
+        }));
+        assert!(mismatch.is_err());
+    }
+
+    #[test]
+    fn temp_file_removed_on_unwind() {
+        let temp = crate::test_support::temp_dir();
+        let temp_path = temp_golden_file_path(&temp.path().join("golden.json"));
+        let result = std::panic::catch_unwind(|| {
+            let _temp_file = TempFile(temp_path.clone());
+            std::fs::write(&temp_path, "partial").unwrap();
+            panic!("Write interrupted");
+        });
+
+        assert!(result.is_err());
+        assert!(!temp_path.exists());
+    }
+
+    #[test]
+    fn save_redacted_with_own_redaction() {
+        #[derive(Clone, Serialize, Deserialize, Debug)]
+        struct Login {
+            user: String,
+            token: String,
+        }
+
+        impl Redactable for Login {
+            fn redact(&mut self) {
+                self.token = REDACTED.to_string();
+            }
+        }
+
+        let mut goldrust = recording("goldrust-save_redacted_with_own_redaction");
+        let login = Login {
+            user: "June".to_string(),
+            token: "secret".to_string(),
+        };
+        goldrust.save_redacted(&login).expect("Failed to save");
+
+        assert_eq!(login.token, "secret");
+        goldrust.assert_matches(&serde_json::json!({"user": "June", "token": REDACTED}));
+    }
+
+    #[cfg(feature = "jsonschema")]
+    #[test]
+    fn assert_schema_reports_all_violations() {
+        let goldrust = Goldrust::with_name("tests/resources/golden", "base-base");
+        let schema = serde_json::json!({"type": "object"});
+        goldrust.assert_schema(&schema);
+
+        let schema = serde_json::json!({
+            "type": "object",
+            "required": ["goldrust_missing"],
+            "properties": {"age": {"type": "string"}},
+        });
+        let panic = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            goldrust.assert_schema(&schema)
+        }))
+        .expect_err("Should panic on violations");
+        let message = panic.downcast_ref::<String>().unwrap();
+        assert!(message.contains("/: \"goldrust_missing\""), "{}", message);
+        assert!(message.contains("/age: 1 is not"), "{}", message);
+    }
+
+    #[test]
+    fn unordered_paths_ignore_array_order() {
+        let temp = crate::test_support::temp_dir();
+        let dir = temp.path();
+        std::fs::write(dir.join("golden.json"), r#"{"tags": ["a", "b", "c"]}"#).unwrap();
+
+        let mut goldrust = Goldrust::with_name(dir, "golden");
+        goldrust.unordered_paths(&["tags"]);
+        goldrust.assert_matches(&serde_json::json!({"tags": ["c", "a", "b"]}));
+    }
+
+    #[test]
+    fn verify_with_unordered_paths() {
+        #[derive(Serialize, Deserialize, PartialEq, Debug)]
+        struct Tagged {
+            tags: Vec<String>,
+        }
+
+        let temp = crate::test_support::temp_dir();
+        let dir = temp.path();
+        std::fs::write(dir.join("golden.json"), r#"{"tags": ["b", "a"]}"#).unwrap();
+
+        let mut goldrust = Goldrust::with_name(dir, "golden");
+        goldrust.unordered_paths(&["tags"]);
+        goldrust.verify(&Tagged {
+            tags: vec!["a".to_string(), "b".to_string()],
+        });
+    }
+
+    #[test]
+    fn verify_with_sort_top_level_by_on_unsorted_golden() {
+        let temp = crate::test_support::temp_dir();
+        let dir = temp.path();
+        std::fs::write(dir.join("golden.json"), r#"[{"id": 2}, {"id": 1}]"#).unwrap();
+
+        let mut goldrust = Goldrust::with_name(dir, "golden");
+        goldrust.sort_top_level_by("id");
+        goldrust.verify(&serde_json::json!([{"id": 1}, {"id": 2}]));
+    }
+
+    #[test]
+    fn ephemeral_removed_on_drop() {
+        let mut goldrust = Goldrust::ephemeral();
+        let dir = goldrust.ephemeral_dir().unwrap().to_path_buf();
+        assert!(goldrust.golden_file_path.starts_with(&dir));
+        goldrust
+            .save(serde_json::json!({"name": "June"}))
+            .expect("Failed to save");
+        assert!(goldrust.golden_file_path.exists());
+
+        drop(goldrust);
+        assert!(!dir.exists());
+    }
+
+    #[test]
+    fn capture_headers_allowlist() {
+        let mut goldrust = recording("goldrust-capture_headers_allowlist");
+        goldrust.capture_headers(&["Content-Type", "link"]);
+        let response = GoldenResponse {
+            status: 200,
+            headers: BTreeMap::from([
+                ("content-type".to_string(), "application/json".to_string()),
+                (
+                    "Link".to_string(),
+                    "<https://example.com?page=2>".to_string(),
+                ),
+                ("set-cookie".to_string(), "session=secret".to_string()),
+                (
+                    "date".to_string(),
+                    "Mon, 01 Jan 2024 00:00:00 GMT".to_string(),
+                ),
+            ]),
+            body: serde_json::json!({}),
+        };
+        goldrust.save_response(&response).expect("Failed to save");
+
+        let saved = goldrust.load_response().expect("Failed to load");
+        assert_eq!(
+            saved.headers.keys().collect::<Vec<_>>(),
+            vec!["Link", "content-type"]
+        );
+    }
+
+    #[test]
+    fn external_call_made_tracks_external_branch() {
+        let mut goldrust = Goldrust::with_name("tests/resources/golden", "base-base");
+        let mut external = false;
+        let save = goldrust.run_blocking(|| {}, || external = true);
+        save(&serde_json::json!({})).expect("Failed to save");
+        assert!(!external);
+        assert!(!goldrust.external_call_made());
+
+        let mut goldrust = recording("goldrust-external_call_made_tracks_external_branch");
+        let save = goldrust.run_blocking(|| {}, || {});
+        save(&serde_json::json!({})).expect("Failed to save");
+        assert!(goldrust.external_call_made());
+    }
+
+    #[test]
+    fn expand_embedded_json_round_trip() {
+        let mut goldrust = recording("goldrust-expand_embedded_json_round_trip");
+        goldrust.expand_embedded_json(true);
+        let content = serde_json::json!({"payload": "{\"id\":1,\"tags\":[\"a\"]}"});
+        goldrust.save(content.clone()).expect("Failed to save");
+
+        let saved: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&goldrust.golden_file_path).unwrap())
+                .unwrap();
+        assert_eq!(
+            saved,
+            serde_json::json!({"payload": {"$embedded_json": {"id": 1, "tags": ["a"]}}})
+        );
+        goldrust.verify(&content);
+        goldrust
+            .assert_matches(&serde_json::json!({"payload": "{ \"id\": 1, \"tags\": [\"a\"] }"}));
+    }
+
+    #[test]
+    fn sort_top_level_by_on_save_and_compare() {
+        let mut goldrust = recording("goldrust-sort_top_level_by_on_save_and_compare");
+        goldrust.sort_top_level_by("id").pretty(false);
+        goldrust
+            .save(serde_json::json!([{"id": 2}, {"id": 1}]))
+            .expect("Failed to save");
+
+        assert_eq!(
+            std::fs::read_to_string(&goldrust.golden_file_path).unwrap(),
+            r#"[{"id":1},{"id":2}]"#
         );
+        goldrust.assert_matches(&serde_json::json!([{"id": 2}, {"id": 1}]));
+    }
+
+    #[test]
+    fn skip_unchanged_keeps_golden_file() {
+        let mut goldrust = recording("goldrust-skip_unchanged_keeps_golden_file");
+        goldrust.skip_unchanged(true);
+        goldrust
+            .save(serde_json::json!({"version": 1}))
+            .expect("Failed to save");
+        let path = goldrust.golden_file_path.to_path_buf();
+        let modified = || {
+            std::fs::metadata(&path)
+                .and_then(|metadata| metadata.modified())
+                .unwrap()
+        };
+        // Any write sets a recent modification time, even with a coarse resolution
+        let marker = std::time::SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(86_400);
+        File::options()
+            .write(true)
+            .open(&path)
+            .and_then(|file| file.set_modified(marker))
+            .unwrap();
+
+        goldrust
+            .save(serde_json::json!({"version": 1}))
+            .expect("Failed to save");
+        assert_eq!(modified(), marker);
+        goldrust
+            .save(serde_json::json!({"version": 2}))
+            .expect("Failed to save");
+        assert_ne!(modified(), marker);
+        goldrust.verify(&serde_json::json!({"version": 2}));
+    }
+
+    #[test]
+    fn template_var_substituted_on_read() {
+        let temp = crate::test_support::temp_dir();
+        let dir = temp.path();
+        std::fs::write(dir.join("golden.json"), r#"{"now": "{{goldrust_now}}"}"#).unwrap();
+
+        let mut goldrust = Goldrust::with_name(dir, "golden");
+        goldrust.template_var("goldrust_now", "2024-01-01T00:00:00Z");
+        let expected = serde_json::json!({"now": "2024-01-01T00:00:00Z"});
+        assert_eq!(goldrust.load_golden().unwrap(), expected);
+        goldrust.verify(&expected);
+    }
+
+    #[test]
+    fn save_reporting_outcomes() {
+        let mut goldrust = recording("goldrust-save_reporting_outcomes");
+        let _ = std::fs::remove_file(&goldrust.golden_file_path);
+
+        let save = |goldrust: &mut Goldrust, version: u8| {
+            goldrust
+                .save_reporting(serde_json::json!({"version": version}))
+                .expect("Failed to save")
+        };
+        assert_eq!(save(&mut goldrust, 1), SaveOutcome::Created);
+        assert_eq!(save(&mut goldrust, 1), SaveOutcome::Unchanged);
+        assert_eq!(save(&mut goldrust, 2), SaveOutcome::Updated);
+        goldrust.verify(&serde_json::json!({"version": 2}));
+    }
+
+    #[test]
+    fn save_returning_serialized_content() {
+        let mut goldrust = recording("goldrust-save_returning_serialized_content");
+        goldrust.redact(&["token"]);
+
+        let bytes = goldrust
+            .save_returning(serde_json::json!({"token": "secret"}))
+            .expect("Failed to save");
+        assert_eq!(bytes, goldrust.load_bytes().unwrap());
+        assert!(!String::from_utf8(bytes).unwrap().contains("secret"));
+    }
+
+    #[test]
+    fn force_save_without_updating_golden_files() {
+        let temp = crate::test_support::temp_dir();
+        let dir = temp.path();
+        std::fs::write(dir.join("golden.json"), r#"{"version": 1}"#).unwrap();
+        let mut goldrust = Goldrust::builder()
+            .dir(dir.to_path_buf())
+            .allow_external_api_call(false)
+            .update_golden_files(false)
+            .golden_file_name("golden".to_string())
+            .build()
+            .expect("Failed to build");
+
+        goldrust
+            .save(serde_json::json!({"version": 2}))
+            .expect("Failed to save");
+        goldrust.verify(&serde_json::json!({"version": 1}));
+        goldrust
+            .force_save(serde_json::json!({"version": 2}))
+            .expect("Failed to force save");
+        goldrust.verify(&serde_json::json!({"version": 2}));
+    }
+
+    #[test]
+    fn save_content_addressed() {
+        let temp = crate::test_support::temp_dir();
+        let dir = temp.path();
+        let content = serde_json::json!({"name": "June"});
+        let mut blobs = Vec::new();
+        for name in ["first", "second"] {
+            let mut goldrust = Goldrust::builder()
+                .dir(dir.to_path_buf())
+                .allow_external_api_call(true)
+                .update_golden_files(true)
+                .golden_file_name(name.to_string())
+                .content_addressed(true)
+                .build()
+                .expect("Failed to build");
+            std::fs::create_dir_all(dir).unwrap();
+            goldrust.save(content.clone()).expect("Failed to save");
+
+            goldrust.verify(&content);
+            blobs.push(blob::resolve(&goldrust.golden_file_path, "json").unwrap());
+        }
+        assert_eq!(blobs[0], blobs[1]);
+    }
+
+    #[test]
+    fn verify_with_float_tolerance() {
+        let mut goldrust = recording("goldrust-verify_with_float_tolerance");
+        goldrust
+            .save(serde_json::json!({"price": 0.30000000000000004}))
+            .expect("Failed to save");
+
+        goldrust.with_float_tolerance(1e-9);
+        goldrust.verify(&serde_json::json!({"price": 0.3}));
+        goldrust.assert_matches(&serde_json::json!({"price": 0.3}));
+    }
+
+    #[test]
+    fn verify_xml_golden_file() {
+        #[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
+        struct User {
+            name: String,
+            age: u16,
+        }
+
+        let temp = crate::test_support::temp_dir();
+        let mut goldrust = Goldrust::builder()
+            .dir(temp.path().to_path_buf())
+            .allow_external_api_call(true)
+            .update_golden_files(true)
+            .golden_file_name("goldrust-verify_xml_golden_file".to_string())
+            .format(GoldenFormat::Xml)
+            .build()
+            .expect("Failed to build");
+        let user = User {
+            name: "June".to_string(),
+            age: 1,
+        };
+        goldrust.save(user.clone()).expect("Failed to save");
+
+        assert_eq!(goldrust.golden_file_path.extension().unwrap(), "xml");
+        goldrust.verify(&user);
+    }
+
+    #[test]
+    fn record_metadata_to_sidecar_file() {
+        let mut goldrust = recording("goldrust-record_metadata_to_sidecar_file");
+        goldrust.record_metadata(true);
+        let content = serde_json::json!({"name": "June"});
+        goldrust.save(content.clone()).expect("Failed to save");
+
+        let metadata = goldrust.load_metadata().expect("Failed to load metadata");
+        assert_eq!(metadata.source, ResponseSource::External);
+        assert_eq!(metadata.goldrust_version, env!("CARGO_PKG_VERSION"));
+        assert!(metadata.recorded_at > 0);
+        let loaded: serde_json::Value = goldrust.read_golden().expect("Failed to load");
+        assert_eq!(loaded, content);
+    }
+
+    #[tokio::test]
+    async fn record_latency_of_external_branch() {
+        let mut goldrust = recording("goldrust-record_latency_of_external_branch");
+        goldrust.record_latency(true);
+        let save = goldrust
+            .run(
+                || async {},
+                || tokio::time::sleep(std::time::Duration::from_millis(20)),
+            )
+            .await
+            .expect("Failed to run");
+        save(&serde_json::json!({})).expect("Failed to save");
+
+        let metadata = goldrust.load_metadata().expect("Failed to load metadata");
+        assert!(metadata.latency().unwrap() >= std::time::Duration::from_millis(20));
+    }
+
+    #[test]
+    fn save_and_load_sequence() {
+        let mut goldrust = recording("goldrust-save_and_load_sequence");
+
+        let responses: Vec<GoldenResponse> = (0..2)
+            .map(|page| GoldenResponse {
+                status: 200,
+                headers: [(
+                    "link".to_string(),
+                    format!("<?page={}>; rel=\"next\"", page + 1),
+                )]
+                .into(),
+                body: serde_json::json!({"page": page}),
+            })
+            .collect();
+        goldrust.save_sequence(&responses).expect("Failed to save");
+
+        assert!(goldrust.named_golden_file_path("page1").exists());
+        assert_eq!(goldrust.load_sequence().expect("Failed to load"), responses);
+    }
+
+    #[test]
+    fn save_bytes_writes_verbatim() {
+        let mut goldrust = recording("goldrust-save_bytes_writes_verbatim");
+        let golden_file_path = goldrust.golden_file_path.clone();
+
+        let content = [0x08, 0x96, 0x01, 0xff];
+        goldrust.save_bytes(&content).expect("Failed to save bytes");
+
+        assert!(goldrust.save_check);
+        assert_eq!(std::fs::read(&golden_file_path).unwrap(), content);
     }
 }
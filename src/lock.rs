@@ -0,0 +1,33 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock, PoisonError};
+
+/// Locks for each golden file, keyed by the absolute path
+///
+/// Tests in the same binary run in parallel by default,
+/// so this prevents torn writes when tests share a golden file.
+fn locks() -> &'static Mutex<HashMap<PathBuf, Arc<Mutex<()>>>> {
+    static LOCKS: OnceLock<Mutex<HashMap<PathBuf, Arc<Mutex<()>>>>> = OnceLock::new();
+    LOCKS.get_or_init(Default::default)
+}
+
+/// Get the lock for the golden file
+///
+/// The lock should be held while writing the golden file.
+pub(crate) fn file_lock(path: &Path) -> Arc<Mutex<()>> {
+    let path = std::path::absolute(path).unwrap_or_else(|_e| path.to_path_buf());
+    let mut locks = locks().lock().unwrap_or_else(PoisonError::into_inner);
+    locks.entry(path).or_default().clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_lock_for_same_file() {
+        let relative = Path::new("tests/resources/golden/base-base.json");
+        let absolute = std::path::absolute(relative).unwrap();
+        assert!(Arc::ptr_eq(&file_lock(relative), &file_lock(&absolute)));
+    }
+}
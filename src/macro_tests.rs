@@ -0,0 +1,34 @@
+//! Tests of the macros naming the golden file after the enclosing test
+//!
+//! The golden file names include a hash of the line and column of each call,
+//! so these tests are kept out of the other test modules, where new tests would move them.
+
+use crate::{assert_golden, goldrust, GoldenFormat, Goldrust};
+
+#[test]
+fn display_goldrust() {
+    let goldrust = goldrust!();
+    assert_eq!(
+        format!("{}", goldrust),
+        format!(
+            "{}, {:?}, {}, {}",
+            goldrust.update_golden_files,
+            goldrust.golden_file_path,
+            goldrust.response_source,
+            goldrust.save_check
+        )
+    );
+}
+
+#[test]
+fn assert_golden_macro() {
+    assert_golden!(serde_json::json!({"name": "June", "age": 1}));
+}
+
+#[test]
+fn assert_golden_macro_with_format() {
+    assert_golden!(
+        serde_json::json!({"name": "June", "age": 1}),
+        format = GoldenFormat::Yaml
+    );
+}
@@ -0,0 +1,74 @@
+use crate::{GoldrustError, ResponseSource};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// The suffix of the metadata sidecar files, appended to the golden file name
+pub(crate) const SUFFIX: &str = ".meta.json";
+
+/// Metadata on how a golden file was recorded
+///
+/// Saved with [`Goldrust::record_metadata`](crate::Goldrust::record_metadata)
+/// to a sidecar file next to the golden file (e.g. `test-name.json.meta.json`),
+/// so the golden file itself stays plain and is compared as usual.
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Debug, Serialize, Deserialize)]
+pub struct GoldenMetadata {
+    /// When the golden file was recorded, in seconds since the Unix epoch
+    pub recorded_at: u64,
+    /// The version of goldrust which recorded the golden file
+    pub goldrust_version: String,
+    /// The source of the response when the golden file was recorded
+    pub source: ResponseSource,
+    /// How long the external call took, in milliseconds,
+    /// when recorded with [`Goldrust::record_latency`](crate::Goldrust::record_latency)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub latency_ms: Option<u64>,
+}
+
+impl GoldenMetadata {
+    /// The metadata of a golden file recorded now
+    pub(crate) fn now(source: ResponseSource, latency: Option<Duration>) -> Self {
+        Self {
+            recorded_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|duration| duration.as_secs())
+                .unwrap_or_default(),
+            goldrust_version: env!("CARGO_PKG_VERSION").to_string(),
+            source,
+            latency_ms: latency.map(|latency| latency.as_millis() as u64),
+        }
+    }
+
+    /// The recorded latency of the external call
+    pub fn latency(&self) -> Option<Duration> {
+        self.latency_ms.map(Duration::from_millis)
+    }
+}
+
+/// The path of the metadata sidecar file of a golden file
+pub(crate) fn metadata_path(golden_file_path: &Path) -> PathBuf {
+    let mut path = golden_file_path.as_os_str().to_owned();
+    path.push(SUFFIX);
+    PathBuf::from(path)
+}
+
+/// Read the metadata sidecar file of a golden file
+pub(crate) fn read(golden_file_path: &Path) -> Result<GoldenMetadata, GoldrustError> {
+    let path = metadata_path(golden_file_path);
+    let bytes =
+        std::fs::read(&path).inspect_err(|_e| tracing::error!(?path, "Error reading file"))?;
+    Ok(serde_json::from_slice(&bytes)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn metadata_path_appends_suffix() {
+        assert_eq!(
+            metadata_path(Path::new("golden/test-name.json")),
+            Path::new("golden/test-name.json.meta.json")
+        );
+    }
+}
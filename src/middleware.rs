@@ -0,0 +1,213 @@
+//! Recording and replaying through a `reqwest-middleware` client
+
+use crate::{sequence_name, GoldenResponse, Goldrust, GoldrustError, ResponseSource};
+use http::Extensions;
+use reqwest::{Request, Response};
+use reqwest_middleware::{Middleware, Next};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, PoisonError};
+
+/// Headers describing the encoding of the original body, which the replayed body doesn't have
+const ENCODING_HEADERS: [&str; 3] = ["content-length", "content-encoding", "transfer-encoding"];
+
+/// A middleware which records responses to golden files, and replays them
+///
+/// With [`ResponseSource::External`], requests go through to the external api,
+/// and each response is saved as a [`GoldenResponse`] on the way back.
+/// With [`ResponseSource::Local`], requests never leave the client,
+/// and the saved responses are replayed instead,
+/// so the client code is the same for both sources.
+///
+/// Responses are saved in the order of the requests,
+/// the same way as [`Goldrust::save_sequence`] (e.g. `test-name__page0.json`),
+/// and can be read back with [`Goldrust::load_sequence`].
+///
+/// ```no_run
+/// # use goldrust::{goldrust, Goldrust, GoldrustMiddleware};
+/// # async fn example() {
+/// let middleware = GoldrustMiddleware::new(goldrust!());
+/// let client = reqwest_middleware::ClientBuilder::new(reqwest::Client::new())
+///     .with(middleware)
+///     .build();
+/// let response = client.get("https://api.example.com/users/1").send().await.unwrap();
+/// # }
+/// ```
+///
+/// Goldrust is dropped with the last clone of the middleware,
+/// so it panics then when no response was recorded while it should have been.
+///
+/// Requires the `reqwest-middleware` feature.
+#[derive(Clone)]
+pub struct GoldrustMiddleware {
+    goldrust: Arc<Mutex<Goldrust>>,
+    calls: Arc<AtomicUsize>,
+}
+
+impl GoldrustMiddleware {
+    /// Record and replay the responses with the Goldrust instance
+    pub fn new(goldrust: Goldrust) -> Self {
+        Self {
+            goldrust: Arc::new(Mutex::new(goldrust)),
+            calls: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    /// The number of requests handled so far
+    pub fn calls(&self) -> usize {
+        self.calls.load(Ordering::SeqCst)
+    }
+
+    fn goldrust(&self) -> std::sync::MutexGuard<'_, Goldrust> {
+        self.goldrust.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Save the response of the request at the index
+    fn record(&self, index: usize, response: &GoldenResponse) -> Result<(), GoldrustError> {
+        let mut goldrust = self.goldrust();
+        let name = sequence_name(index);
+        if !goldrust.mark_named_saved(&name) {
+            return Ok(());
+        }
+        let path = goldrust.named_golden_file_path(&name);
+        goldrust.write_response(&path, response)
+    }
+
+    /// Load the saved response of the request at the index
+    fn replay(&self, index: usize) -> Result<GoldenResponse, GoldrustError> {
+        let goldrust = self.goldrust();
+        goldrust.read_golden_file(&goldrust.named_golden_file_path(&sequence_name(index)))
+    }
+}
+
+#[async_trait::async_trait]
+impl Middleware for GoldrustMiddleware {
+    async fn handle(
+        &self,
+        req: Request,
+        extensions: &mut Extensions,
+        next: Next<'_>,
+    ) -> reqwest_middleware::Result<Response> {
+        let index = self.calls.fetch_add(1, Ordering::SeqCst);
+        let response_source = self.goldrust().response_source.clone();
+        let response = match response_source {
+            ResponseSource::Local => {
+                tracing::debug!(index, url = %req.url(), "Replaying the golden response");
+                self.replay(index)
+                    .map_err(reqwest_middleware::Error::middleware)?
+            }
+            ResponseSource::External => {
+                self.goldrust().external_calls += 1;
+                let started = std::time::Instant::now();
+                let response = next.run(req, extensions).await?;
+                let response = GoldenResponse::from_reqwest(response).await?;
+                self.goldrust().latency = Some(started.elapsed());
+                self.record(index, &response)
+                    .map_err(reqwest_middleware::Error::middleware)?;
+                response
+            }
+        };
+        into_reqwest(&response).map_err(reqwest_middleware::Error::middleware)
+    }
+}
+
+/// Build a [`reqwest::Response`] from the golden response
+fn into_reqwest(response: &GoldenResponse) -> Result<Response, GoldrustError> {
+    let invalid = |reason: String| GoldrustError::InvalidConfig { reason };
+    let mut builder = http::Response::builder().status(response.status);
+    for (name, value) in &response.headers {
+        if !ENCODING_HEADERS.contains(&name.to_ascii_lowercase().as_str()) {
+            builder = builder.header(name, value);
+        }
+    }
+    let response = builder.body(response.body_bytes()?).map_err(|e| {
+        invalid(format!(
+            "The golden response is not a valid response: {}",
+            e
+        ))
+    })?;
+    Ok(Response::from(response))
+}
+
+#[cfg(all(test, feature = "wiremock"))]
+mod tests {
+    use super::*;
+    use wiremock::matchers::method;
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn client(goldrust: Goldrust) -> reqwest_middleware::ClientWithMiddleware {
+        reqwest_middleware::ClientBuilder::new(reqwest::Client::new())
+            .with(GoldrustMiddleware::new(goldrust))
+            .build()
+    }
+
+    fn goldrust(dir: &std::path::Path, external: bool) -> Goldrust {
+        Goldrust::builder()
+            .dir(dir.to_path_buf())
+            .allow_external_api_call(external)
+            .update_golden_files(external)
+            .golden_file_name("middleware".to_string())
+            .build()
+            .expect("Failed to build")
+    }
+
+    #[tokio::test]
+    async fn middleware_records_then_replays() {
+        let temp = crate::test_support::temp_dir();
+        let dir = temp.path();
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(
+                ResponseTemplate::new(201)
+                    .insert_header("x-page", "1")
+                    .set_body_json(serde_json::json!({"name": "June"})),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let recorded = client(goldrust(dir, true))
+            .get(server.uri())
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(recorded.status(), 201);
+        assert!(dir.join("middleware__page0.json").exists());
+
+        let replayed = client(goldrust(dir, false))
+            .get("http://goldrust.invalid")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(replayed.status(), 201);
+        assert_eq!(replayed.headers()["x-page"], "1");
+        assert_eq!(
+            replayed.json::<serde_json::Value>().await.unwrap(),
+            serde_json::json!({"name": "June"})
+        );
+    }
+
+    #[tokio::test]
+    async fn middleware_replays_json_string_body_verbatim() {
+        let temp = crate::test_support::temp_dir();
+        let dir = temp.path();
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(200).set_body_json("abc"))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        client(goldrust(dir, true))
+            .get(server.uri())
+            .send()
+            .await
+            .unwrap();
+
+        let replayed = client(goldrust(dir, false))
+            .get("http://goldrust.invalid")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(replayed.text().await.unwrap(), r#""abc""#);
+    }
+}
@@ -0,0 +1,428 @@
+//! Helpers to serve golden files from mock servers
+
+use crate::{metadata, GoldenResponse, Goldrust};
+use std::path::Path;
+use std::time::Duration;
+
+/// Headers which describe the original transfer,
+/// and are set by the mock server itself
+const TRANSFER_HEADERS: [&str; 2] = ["content-length", "transfer-encoding"];
+
+/// The response to serve from a mock server
+struct MockResponse {
+    status: u16,
+    headers: Vec<(String, String)>,
+    body: Vec<u8>,
+    /// The latency recorded with the golden file, which the response is delayed by
+    delay: Option<Duration>,
+}
+
+impl MockResponse {
+    /// Read the mock response from the golden file
+    ///
+    /// Golden files saved with [`Goldrust::save_response`] keep the stored status and headers,
+    /// while other golden files are served verbatim with a `200` status.
+    ///
+    /// # Panics
+    ///
+    /// Panics when the golden file can't be read.
+    #[track_caller]
+    fn from_golden(goldrust: &Goldrust) -> Self {
+        Self::from_path(goldrust, &goldrust.golden_file_path)
+    }
+
+    /// Read the mock response from the golden file at the path
+    ///
+    /// Same as [`MockResponse::from_golden`], for named golden files.
+    #[track_caller]
+    fn from_path(goldrust: &Goldrust, path: &Path) -> Self {
+        let delay = metadata::read(&goldrust.read_path(path))
+            .ok()
+            .and_then(|metadata| metadata.latency());
+        let Ok(response) = goldrust.read_golden_file::<GoldenResponse>(path) else {
+            return Self {
+                status: 200,
+                headers: Vec::new(),
+                body: goldrust.load_file(path).unwrap_or_else(|e| panic!("{}", e)),
+                delay,
+            };
+        };
+        let GoldenResponse {
+            status,
+            headers,
+            body,
+        } = response;
+
+        let body = crate::body::to_bytes(&body).unwrap_or_else(|e| panic!("{}", e));
+        let headers = headers
+            .into_iter()
+            .filter(|(name, _)| !TRANSFER_HEADERS.contains(&name.to_ascii_lowercase().as_str()))
+            .collect();
+        Self {
+            status,
+            headers,
+            body,
+            delay,
+        }
+    }
+}
+
+/// Create a [`wiremock::ResponseTemplate`] which serves the golden file
+///
+/// Golden files saved with [`Goldrust::save_response`] reproduce the stored status and headers.
+/// Other golden files are served verbatim with a `200` status.
+///
+/// Requires the `wiremock` feature.
+///
+/// # Panics
+///
+/// Panics when the golden file can't be read.
+#[cfg(feature = "wiremock")]
+#[track_caller]
+pub fn wiremock_template(goldrust: &Goldrust) -> wiremock::ResponseTemplate {
+    MockResponse::from_golden(goldrust).into_wiremock()
+}
+
+/// Create a [`wiremock::ResponseTemplate`] which serves the golden file for a query string
+///
+/// Same as [`wiremock_template`], for golden files saved with [`Goldrust::save_query`].
+/// Combine with [`wiremock_query`] to serve each query string its own golden file.
+///
+/// Requires the `wiremock` feature.
+///
+/// # Panics
+///
+/// Panics when the golden file can't be read.
+#[cfg(feature = "wiremock")]
+#[track_caller]
+pub fn wiremock_query_template(goldrust: &Goldrust, query: &str) -> wiremock::ResponseTemplate {
+    MockResponse::from_path(goldrust, &goldrust.query_golden_file_path(query)).into_wiremock()
+}
+
+/// A running [`wiremock::MockServer`] serving the golden file, created with [`Goldrust::serve`]
+///
+/// The server is shut down when this is dropped.
+///
+/// Requires the `wiremock` feature.
+#[cfg(feature = "wiremock")]
+pub struct ServingMock {
+    server: wiremock::MockServer,
+}
+
+#[cfg(feature = "wiremock")]
+impl ServingMock {
+    /// The base URL of the server, to point the client at
+    pub fn uri(&self) -> String {
+        self.server.uri()
+    }
+
+    /// The underlying server, e.g. to inspect the received requests
+    pub fn server(&self) -> &wiremock::MockServer {
+        &self.server
+    }
+}
+
+#[cfg(feature = "wiremock")]
+impl Goldrust {
+    /// Start a [`wiremock::MockServer`] which serves the golden file for every request
+    ///
+    /// This replaces the mock setup for the common single-response case:
+    /// the golden file is served with [`wiremock_template`] at any method and path,
+    /// so the client only has to be pointed at [`ServingMock::uri`].
+    ///
+    /// Requires the `wiremock` feature.
+    ///
+    /// # Panics
+    ///
+    /// Panics when the golden file can't be read.
+    pub async fn serve(&self) -> ServingMock {
+        let template = wiremock_template(self);
+        let server = wiremock::MockServer::start().await;
+        wiremock::Mock::given(wiremock::matchers::any())
+            .respond_with(template)
+            .mount(&server)
+            .await;
+        ServingMock { server }
+    }
+}
+
+/// A [`wiremock::Match`] for requests with the query string
+///
+/// Created with [`wiremock_query`].
+#[cfg(feature = "wiremock")]
+#[derive(Clone, Debug)]
+pub struct WiremockQuery(String);
+
+/// Match requests whose query string is the same as `query`
+///
+/// Both query strings are normalized with [`normalize_query`](crate::normalize_query),
+/// so the order of the parameters doesn't matter.
+/// Unlike [`wiremock::matchers::query_param`], extra parameters don't match.
+///
+/// Requires the `wiremock` feature.
+#[cfg(feature = "wiremock")]
+pub fn wiremock_query(query: &str) -> WiremockQuery {
+    WiremockQuery(crate::normalize_query(query))
+}
+
+#[cfg(feature = "wiremock")]
+impl wiremock::Match for WiremockQuery {
+    fn matches(&self, request: &wiremock::Request) -> bool {
+        crate::normalize_query(request.url.query().unwrap_or("")) == self.0
+    }
+}
+
+#[cfg(feature = "wiremock")]
+impl MockResponse {
+    fn into_wiremock(self) -> wiremock::ResponseTemplate {
+        let mut template = wiremock::ResponseTemplate::new(self.status);
+        for (name, value) in self.headers {
+            template = template.insert_header(name.as_str(), value.as_str());
+        }
+        if let Some(delay) = self.delay {
+            template = template.set_delay(delay);
+        }
+        if self.body.is_empty() {
+            return template;
+        }
+        template.set_body_bytes(self.body)
+    }
+}
+
+/// Register a mock on the [`httpmock::MockServer`] which serves the golden file
+/// for requests matching `method` and `path`
+///
+/// Same as [`wiremock_template`],
+/// golden files saved with [`Goldrust::save_response`] reproduce the stored status and headers,
+/// while other golden files are served verbatim with a `200` status.
+///
+/// Requires the `httpmock` feature.
+///
+/// # Panics
+///
+/// Panics when the golden file can't be read.
+#[cfg(feature = "httpmock")]
+#[track_caller]
+pub fn httpmock_mock<'a>(
+    goldrust: &Goldrust,
+    server: &'a httpmock::MockServer,
+    method: impl Into<httpmock::Method>,
+    path: &str,
+) -> httpmock::Mock<'a> {
+    let response = MockResponse::from_golden(goldrust);
+    response.into_httpmock(server, method, path, "")
+}
+
+/// Register a mock on the [`httpmock::MockServer`] which serves the golden file for a query string
+/// for requests matching `method`, `path` and the parameters of `query`
+///
+/// Same as [`httpmock_mock`], for golden files saved with [`Goldrust::save_query`].
+/// The order of the parameters doesn't matter.
+///
+/// Requires the `httpmock` feature.
+///
+/// # Panics
+///
+/// Panics when the golden file can't be read.
+#[cfg(feature = "httpmock")]
+#[track_caller]
+pub fn httpmock_query_mock<'a>(
+    goldrust: &Goldrust,
+    server: &'a httpmock::MockServer,
+    method: impl Into<httpmock::Method>,
+    path: &str,
+    query: &str,
+) -> httpmock::Mock<'a> {
+    let response = MockResponse::from_path(goldrust, &goldrust.query_golden_file_path(query));
+    response.into_httpmock(server, method, path, query)
+}
+
+#[cfg(feature = "httpmock")]
+impl MockResponse {
+    fn into_httpmock<'a>(
+        self,
+        server: &'a httpmock::MockServer,
+        method: impl Into<httpmock::Method>,
+        path: &str,
+        query: &str,
+    ) -> httpmock::Mock<'a> {
+        server.mock(|when, then| {
+            let mut when = when.method(method).path(path);
+            for (key, value) in crate::query::parameters(query) {
+                when = when.query_param(key, value);
+            }
+            let mut then = then.status(self.status);
+            for (name, value) in self.headers {
+                then = then.header(name, value);
+            }
+            if let Some(delay) = self.delay {
+                then = then.delay(delay);
+            }
+            if !self.body.is_empty() {
+                then.body(self.body);
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::recording;
+
+    #[test]
+    fn mock_response_from_saved_response() {
+        let mut goldrust = recording("goldrust-mock_response_from_saved_response");
+        goldrust
+            .save_response(&GoldenResponse {
+                status: 404,
+                headers: [
+                    ("x-request-id".to_string(), "1".to_string()),
+                    ("Content-Length".to_string(), "100".to_string()),
+                ]
+                .into(),
+                body: serde_json::json!({"error": "not found"}),
+            })
+            .expect("Failed to save");
+
+        let response = MockResponse::from_golden(&goldrust);
+        assert_eq!(response.status, 404);
+        assert_eq!(
+            response.headers,
+            vec![("x-request-id".to_string(), "1".to_string())]
+        );
+        assert_eq!(response.body, br#"{"error":"not found"}"#);
+    }
+
+    #[test]
+    fn mock_response_delayed_by_recorded_latency() {
+        let mut goldrust = recording("goldrust-mock_response_delayed_by_recorded_latency");
+        goldrust.record_latency(true);
+        let save = goldrust.run_blocking(|| {}, || std::thread::sleep(Duration::from_millis(20)));
+        save(&serde_json::json!({})).expect("Failed to save");
+
+        let response = MockResponse::from_golden(&goldrust);
+        assert!(response.delay.unwrap() >= Duration::from_millis(20));
+    }
+
+    #[test]
+    fn mock_response_without_body() {
+        let mut goldrust = recording("goldrust-mock_response_without_body");
+        goldrust
+            .save_response(&GoldenResponse::empty(204))
+            .expect("Failed to save");
+
+        let response = MockResponse::from_golden(&goldrust);
+        assert_eq!(response.status, 204);
+        assert!(response.body.is_empty());
+
+        let loaded = goldrust.load_response().expect("Failed to load");
+        assert!(loaded.has_empty_body());
+        assert_eq!(
+            loaded,
+            GoldenResponse {
+                body: serde_json::json!(""),
+                ..GoldenResponse::empty(204)
+            }
+        );
+    }
+
+    #[test]
+    fn mock_response_from_body() {
+        let goldrust = Goldrust::with_name("tests/resources/golden", "base-base");
+
+        let response = MockResponse::from_golden(&goldrust);
+        assert_eq!(response.status, 200);
+        assert_eq!(response.body, goldrust.load_bytes().unwrap());
+    }
+
+    #[cfg(feature = "wiremock")]
+    #[tokio::test]
+    async fn wiremock_query_serves_golden_file_per_query() {
+        let mut goldrust = recording("goldrust-wiremock_query_serves_golden_file_per_query");
+        goldrust
+            .save_query("q=rust&page=1", serde_json::json!({"q": "rust"}))
+            .expect("Failed to save");
+        goldrust
+            .save_query("q=go", serde_json::json!({"q": "go"}))
+            .expect("Failed to save");
+
+        let mock_server = wiremock::MockServer::start().await;
+        for query in ["page=1&q=rust", "q=go"] {
+            wiremock::Mock::given(wiremock::matchers::path("/search"))
+                .and(wiremock_query(query))
+                .respond_with(wiremock_query_template(&goldrust, query))
+                .mount(&mock_server)
+                .await;
+        }
+
+        for (query, expected) in [("q=rust&page=1", "rust"), ("q=go", "go")] {
+            let response = reqwest::get(format!("{}/search?{}", mock_server.uri(), query))
+                .await
+                .expect("Failed to send");
+            let body: serde_json::Value = response.json().await.expect("Failed to read body");
+            assert_eq!(body, serde_json::json!({"q": expected}));
+        }
+        let response = reqwest::get(format!("{}/search?q=rust", mock_server.uri()))
+            .await
+            .expect("Failed to send");
+        assert_eq!(response.status(), 404);
+    }
+
+    #[cfg(feature = "wiremock")]
+    #[tokio::test]
+    async fn serve_golden_file() {
+        let goldrust = Goldrust::with_name("tests/resources/golden", "base-base");
+        let mock = goldrust.serve().await;
+
+        let response = reqwest::get(format!("{}/any/path", mock.uri()))
+            .await
+            .expect("Failed to send");
+        assert_eq!(response.status(), 200);
+        assert_eq!(
+            response.bytes().await.expect("Failed to read body"),
+            goldrust.load_bytes().unwrap()
+        );
+        assert_eq!(mock.server().received_requests().await.unwrap().len(), 1);
+    }
+
+    #[cfg(feature = "wiremock")]
+    #[tokio::test]
+    async fn wiremock_template_serves_golden_file() {
+        let goldrust = Goldrust::with_name("tests/resources/golden", "base-base");
+
+        let mock_server = wiremock::MockServer::start().await;
+        wiremock::Mock::given(wiremock::matchers::path("/"))
+            .respond_with(wiremock_template(&goldrust))
+            .mount(&mock_server)
+            .await;
+
+        let response = reqwest::get(mock_server.uri())
+            .await
+            .expect("Failed to send");
+        assert_eq!(response.status(), 200);
+        assert_eq!(
+            response.bytes().await.expect("Failed to read body"),
+            goldrust.load_bytes().unwrap()
+        );
+    }
+
+    #[cfg(feature = "httpmock")]
+    #[tokio::test]
+    async fn httpmock_mock_serves_golden_file() {
+        let goldrust = Goldrust::with_name("tests/resources/golden", "base-base");
+
+        let server = httpmock::MockServer::start_async().await;
+        let mock = httpmock_mock(&goldrust, &server, "GET", "/api");
+
+        let response = reqwest::get(server.url("/api"))
+            .await
+            .expect("Failed to send");
+        assert_eq!(response.status(), 200);
+        assert_eq!(
+            response.bytes().await.expect("Failed to read body"),
+            goldrust.load_bytes().unwrap()
+        );
+        mock.assert_async().await;
+    }
+}
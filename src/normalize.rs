@@ -0,0 +1,75 @@
+use regex::Regex;
+use serde_json::Value;
+use std::sync::OnceLock;
+
+/// A function normalizing volatile values in place
+///
+/// Normalizers are applied both when saving and when comparing,
+/// so that values which change on every call don't produce noisy diffs.
+pub type Normalizer = fn(&mut Value);
+
+/// The value which RFC3339 timestamps are replaced with by [`normalize_volatile`]
+pub const TIMESTAMP_PLACEHOLDER: &str = "<timestamp>";
+/// The value which UUIDs are replaced with by [`normalize_volatile`]
+pub const UUID_PLACEHOLDER: &str = "<uuid>";
+
+fn rfc3339() -> &'static Regex {
+    static RFC3339: OnceLock<Regex> = OnceLock::new();
+    RFC3339.get_or_init(|| {
+        Regex::new(r"^\d{4}-\d{2}-\d{2}[Tt ]\d{2}:\d{2}:\d{2}(\.\d+)?([Zz]|[+-]\d{2}:\d{2})$")
+            .expect("Invalid RFC3339 regex")
+    })
+}
+
+fn uuid() -> &'static Regex {
+    static UUID: OnceLock<Regex> = OnceLock::new();
+    UUID.get_or_init(|| {
+        Regex::new(r"^[0-9a-fA-F]{8}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{12}$")
+            .expect("Invalid UUID regex")
+    })
+}
+
+/// Apply the normalizers to the value, in order
+pub(crate) fn apply(value: &mut Value, normalizers: &[Normalizer]) {
+    for normalizer in normalizers {
+        normalizer(value);
+    }
+}
+
+/// Replace RFC3339 timestamps and UUIDs with placeholders, recursively
+///
+/// Only whole string values are replaced,
+/// with [`TIMESTAMP_PLACEHOLDER`] and [`UUID_PLACEHOLDER`] respectively.
+pub fn normalize_volatile(value: &mut Value) {
+    match value {
+        Value::String(s) if rfc3339().is_match(s) => *s = TIMESTAMP_PLACEHOLDER.to_string(),
+        Value::String(s) if uuid().is_match(s) => *s = UUID_PLACEHOLDER.to_string(),
+        Value::Array(values) => values.iter_mut().for_each(normalize_volatile),
+        Value::Object(map) => map.values_mut().for_each(normalize_volatile),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn normalize_volatile_values() {
+        let mut value = json!({
+            "id": "67e55044-10b1-426f-9247-bb680e5fe0c8",
+            "created_at": "2024-09-20T12:34:56.789Z",
+            "items": [{"updated_at": "2024-09-20T12:34:56+09:00", "name": "2024-09-20"}]
+        });
+        normalize_volatile(&mut value);
+        assert_eq!(
+            value,
+            json!({
+                "id": UUID_PLACEHOLDER,
+                "created_at": TIMESTAMP_PLACEHOLDER,
+                "items": [{"updated_at": TIMESTAMP_PLACEHOLDER, "name": "2024-09-20"}]
+            })
+        );
+    }
+}
@@ -0,0 +1,113 @@
+//! Paths pointing into a [`serde_json::Value`]
+//!
+//! Paths are dot separated object keys, where each key can be followed by an array selector:
+//! - `access_token`: The `access_token` field of the top-level object
+//! - `credentials.client_secret`: A nested field
+//! - `items[*].token`: The `token` field of every element of the `items` array
+//! - `items[0].token`: The `token` field of the first element of the `items` array
+//! - `[*].id`: The `id` field of every element of a top-level array
+
+use serde_json::Value;
+
+#[derive(Clone, Eq, PartialEq, Debug)]
+enum Segment {
+    Key(String),
+    Index(usize),
+    Wildcard,
+}
+
+fn parse(path: &str) -> Vec<Segment> {
+    let mut segments = Vec::new();
+    for part in path.split('.') {
+        let (key, mut selectors) = match part.find('[') {
+            Some(i) => part.split_at(i),
+            None => (part, ""),
+        };
+        if !key.is_empty() {
+            segments.push(Segment::Key(key.to_string()));
+        }
+        while let Some(rest) = selectors.strip_prefix('[') {
+            let Some((selector, rest)) = rest.split_once(']') else {
+                tracing::warn!(path, "Unclosed array selector in path");
+                break;
+            };
+            match selector {
+                "*" => segments.push(Segment::Wildcard),
+                index => match index.parse() {
+                    Ok(index) => segments.push(Segment::Index(index)),
+                    Err(_) => tracing::warn!(path, index, "Invalid array index in path"),
+                },
+            }
+            selectors = rest;
+        }
+    }
+    segments
+}
+
+/// Call `f` on every value the path points to
+///
+/// Parts of the path which do not exist in the value are skipped.
+pub(crate) fn visit_mut<F>(value: &mut Value, path: &str, mut f: F)
+where
+    F: FnMut(&mut Value),
+{
+    visit_segments_mut(value, &parse(path), &mut f);
+}
+
+fn visit_segments_mut<F>(value: &mut Value, segments: &[Segment], f: &mut F)
+where
+    F: FnMut(&mut Value),
+{
+    let Some((segment, rest)) = segments.split_first() else {
+        f(value);
+        return;
+    };
+    match (segment, value) {
+        (Segment::Key(key), Value::Object(map)) => {
+            if let Some(value) = map.get_mut(key) {
+                visit_segments_mut(value, rest, f);
+            }
+        }
+        (Segment::Index(index), Value::Array(values)) => {
+            if let Some(value) = values.get_mut(*index) {
+                visit_segments_mut(value, rest, f);
+            }
+        }
+        (Segment::Wildcard, Value::Array(values)) => {
+            for value in values {
+                visit_segments_mut(value, rest, f);
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn parse_path() {
+        assert_eq!(
+            parse("items[*].token"),
+            vec![
+                Segment::Key("items".to_string()),
+                Segment::Wildcard,
+                Segment::Key("token".to_string())
+            ]
+        );
+        assert_eq!(
+            parse("[0].id"),
+            vec![Segment::Index(0), Segment::Key("id".to_string())]
+        );
+    }
+
+    #[test]
+    fn visit_mut_skips_missing() {
+        let mut value = json!({"items": [{"token": 1}, {"other": 2}]});
+        let mut visited = 0;
+        visit_mut(&mut value, "items[*].token", |_| visited += 1);
+        assert_eq!(visited, 1);
+    }
+}
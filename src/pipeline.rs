@@ -0,0 +1,165 @@
+use crate::codec::{self, Codec};
+use crate::normalize::{self, Normalizer};
+use crate::redact::{self, Redaction};
+use crate::{blob, canonical, compare, compress, embedded, review};
+use crate::{lookup_path, write_golden_file, GoldenFormat, Goldrust, GoldrustError};
+use std::path::{Path, PathBuf};
+
+/// A snapshot of the options deciding how Goldrust reads and writes golden files
+///
+/// This is `Send + 'static`, so that [`Goldrust::save_async`] runs the same steps
+/// as [`Goldrust::save`] on a blocking thread.
+#[derive(Clone, Debug)]
+pub(crate) struct SavePipeline {
+    format: GoldenFormat,
+    codec: Option<Codec>,
+    pretty: bool,
+    canonical: bool,
+    sort_keys: bool,
+    expand_embedded_json: bool,
+    top_level_sort_key: Option<String>,
+    normalizers: Vec<Normalizer>,
+    redactions: Vec<Redaction>,
+    before_save: Vec<fn(serde_json::Value) -> serde_json::Value>,
+    skip_unchanged: bool,
+    content_addressed: bool,
+    compress: bool,
+    extension: String,
+    hasher: blob::Hasher,
+    forbid_update: bool,
+    create_dirs: bool,
+    review: bool,
+    golden_file_dir: PathBuf,
+    fallback_dirs: Vec<PathBuf>,
+}
+
+impl SavePipeline {
+    pub(crate) fn new(goldrust: &Goldrust) -> Self {
+        Self {
+            format: goldrust.format,
+            codec: goldrust.codec.clone(),
+            pretty: goldrust.pretty,
+            canonical: goldrust.canonical,
+            sort_keys: goldrust.sort_keys,
+            expand_embedded_json: goldrust.expand_embedded_json,
+            top_level_sort_key: goldrust.top_level_sort_key.clone(),
+            normalizers: goldrust.normalizers.clone(),
+            redactions: goldrust.redactions.clone(),
+            before_save: goldrust.before_save.clone(),
+            skip_unchanged: goldrust.skip_unchanged,
+            content_addressed: goldrust.content_addressed,
+            compress: goldrust.compress,
+            extension: goldrust.extension.clone(),
+            hasher: goldrust.hasher.clone(),
+            forbid_update: goldrust.forbid_update,
+            create_dirs: goldrust.create_dirs,
+            review: goldrust.review,
+            golden_file_dir: goldrust.golden_file_dir.clone(),
+            fallback_dirs: goldrust.fallback_dirs.clone(),
+        }
+    }
+
+    /// Apply normalizers, redactions and the other transformations done right before saving
+    pub(crate) fn prepare(&self, value: &mut serde_json::Value) {
+        normalize::apply(value, &self.normalizers);
+        redact::redact_with(value, &self.redactions);
+        *value = self
+            .before_save
+            .iter()
+            .fold(std::mem::take(value), |value, hook| hook(value));
+        if let Some(key) = &self.top_level_sort_key {
+            compare::sort_top_level_by(value, key);
+        }
+        if self.expand_embedded_json {
+            embedded::expand(value);
+        }
+        if self.canonical {
+            canonical::canonicalize(value);
+        } else if self.sort_keys {
+            value.sort_all_objects();
+        }
+    }
+
+    /// Serialize the value into the content of the golden file at `path`
+    pub(crate) fn serialize(
+        &self,
+        path: &Path,
+        value: &serde_json::Value,
+    ) -> Result<Vec<u8>, GoldrustError> {
+        codec::serialize(
+            self.format,
+            self.codec.as_ref(),
+            value,
+            self.pretty && !self.canonical,
+        )
+        .inspect_err(|e| tracing::error!(?path, %e, "Error serializing content"))
+    }
+
+    /// Read the content of the golden file, resolving blobs and decompressing when configured
+    pub(crate) fn load(&self, path: &Path) -> Result<Vec<u8>, GoldrustError> {
+        let path = &self.read_path(path);
+        let blob_path;
+        let path = if self.content_addressed {
+            blob_path = blob::resolve(path, &self.extension)?;
+            &blob_path
+        } else {
+            path
+        };
+        let bytes =
+            std::fs::read(path).inspect_err(|_e| tracing::error!(?path, "Error reading file"))?;
+        if !self.compress {
+            return Ok(bytes);
+        }
+        Ok(compress::decompress(&bytes)
+            .inspect_err(|_e| tracing::error!(?path, "Error decompressing file"))?)
+    }
+
+    /// Write the bytes to the golden file, compressing them when configured
+    ///
+    /// Returns the path which was written,
+    /// or `None` when the golden file is unchanged and `skip_unchanged` is set.
+    pub(crate) fn write(
+        &self,
+        path: &Path,
+        bytes: &[u8],
+    ) -> Result<Option<PathBuf>, GoldrustError> {
+        if self.skip_unchanged && self.load(path).is_ok_and(|existing| existing == bytes) {
+            tracing::debug!(?path, "Golden file is unchanged, skipping write");
+            return Ok(None);
+        }
+        let path = self.write_path(path)?;
+        if self.content_addressed {
+            blob::write(&path, bytes, &self.extension, self.compress, &self.hasher)?;
+        } else {
+            write_golden_file(&path, bytes, self.compress)?;
+        }
+        Ok(Some(path))
+    }
+
+    /// The path a golden file is read from, looking up the fallback directories
+    pub(crate) fn read_path(&self, path: &Path) -> PathBuf {
+        lookup_path(path, &self.golden_file_dir, &self.fallback_dirs)
+    }
+
+    /// The path which is actually written when saving to the golden file
+    ///
+    /// This is the pending golden file when recording for review.
+    pub(crate) fn write_path(&self, path: &Path) -> Result<PathBuf, GoldrustError> {
+        if self.forbid_update {
+            return Err(GoldrustError::UpdateForbidden {
+                path: path.to_path_buf(),
+            });
+        }
+        if self.create_dirs {
+            if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+                std::fs::create_dir_all(dir)
+                    .inspect_err(|_e| tracing::error!(?dir, "Error creating directory"))?;
+            }
+        }
+        if self.review {
+            Ok(review::pending_path(path))
+        } else {
+            Ok(path.to_path_buf())
+        }
+    }
+}
@@ -0,0 +1,71 @@
+//! Query strings, normalized so the order of the parameters doesn't matter
+
+/// Characters which can't be used in file names on every platform
+const INVALID_FILE_NAME_CHARS: [char; 9] = ['/', '\\', ':', '*', '?', '"', '<', '>', '|'];
+
+/// Normalize a query string, so the order of the parameters doesn't matter
+///
+/// A leading `?` and empty parameters are dropped,
+/// and the parameters are sorted by key, then value,
+/// e.g. `?q=rust&page=1` → `page=1&q=rust`.
+/// Repeated keys are kept, and values are compared as written, without percent-decoding.
+pub fn normalize_query(query: &str) -> String {
+    parameters(query)
+        .into_iter()
+        .map(|(key, value)| format!("{}={}", key, value))
+        .collect::<Vec<_>>()
+        .join("&")
+}
+
+/// The sorted parameters of a query string
+///
+/// Parameters without a `=` get an empty value.
+pub(crate) fn parameters(query: &str) -> Vec<(String, String)> {
+    let query = query.strip_prefix('?').unwrap_or(query);
+    let mut parameters: Vec<(String, String)> = query
+        .split('&')
+        .filter(|parameter| !parameter.is_empty())
+        .map(|parameter| {
+            let (key, value) = parameter.split_once('=').unwrap_or((parameter, ""));
+            (key.to_string(), value.to_string())
+        })
+        .collect();
+    parameters.sort();
+    parameters
+}
+
+/// The name of the named golden file for a query string
+///
+/// The normalized query string is used as is,
+/// except for characters which can't be used in file names.
+pub(crate) fn query_name(query: &str) -> String {
+    normalize_query(query)
+        .chars()
+        .map(|c| {
+            if INVALID_FILE_NAME_CHARS.contains(&c) {
+                '_'
+            } else {
+                c
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalize_query_sorts_parameters() {
+        assert_eq!(normalize_query("?q=rust&page=1&&tag"), "page=1&q=rust&tag=");
+        assert_eq!(
+            normalize_query("tag=b&tag=a"),
+            normalize_query("tag=a&tag=b")
+        );
+    }
+
+    #[test]
+    fn query_name_replaces_invalid_characters() {
+        assert_eq!(query_name("path=a/b&q=x?"), "path=a_b&q=x_");
+    }
+}
@@ -0,0 +1,109 @@
+use crate::path;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::cmp::Ordering;
+
+/// The value which redacted fields are replaced with
+pub const REDACTED: &str = "<redacted>";
+
+/// A type which scrubs its own sensitive fields before being saved
+///
+/// Implement this to keep the redaction logic next to the type,
+/// instead of listing field paths with [`Goldrust::redact`](crate::Goldrust::redact),
+/// and save with [`Goldrust::save_redacted`](crate::Goldrust::save_redacted).
+pub trait Redactable {
+    /// Replace the sensitive fields, e.g. with [`REDACTED`]
+    fn redact(&mut self);
+}
+
+/// A field path to redact, and the value it is replaced with
+#[derive(Clone, Eq, PartialEq, Debug, Serialize, Deserialize)]
+pub(crate) struct Redaction {
+    pub(crate) path: String,
+    pub(crate) replacement: Value,
+}
+
+impl Redaction {
+    /// Redact the path with [`REDACTED`]
+    pub(crate) fn new(path: &str) -> Self {
+        Self {
+            path: path.to_string(),
+            replacement: Value::String(REDACTED.to_string()),
+        }
+    }
+}
+
+// `Value` isn't `Ord`, so replacements are ordered by their serialization
+impl Ord for Redaction {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.path.cmp(&other.path).then_with(|| {
+            self.replacement
+                .to_string()
+                .cmp(&other.replacement.to_string())
+        })
+    }
+}
+
+impl PartialOrd for Redaction {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Replace the values at each path with the replacement of the path
+///
+/// Check [`Goldrust::redact`](crate::Goldrust::redact) for the path syntax.
+pub(crate) fn redact_with(value: &mut Value, redactions: &[Redaction]) {
+    for redaction in redactions {
+        path::visit_mut(value, &redaction.path, |value| {
+            *value = redaction.replacement.clone();
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn redact_nested_and_wildcard() {
+        let mut value = json!({
+            "access_token": "secret",
+            "credentials": {"client_id": "id", "client_secret": "secret"},
+            "items": [{"token": "secret", "name": "a"}, {"token": "secret", "name": "b"}]
+        });
+        redact_with(
+            &mut value,
+            &[
+                Redaction::new("access_token"),
+                Redaction::new("credentials.client_secret"),
+                Redaction::new("items[*].token"),
+            ],
+        );
+        assert_eq!(
+            value,
+            json!({
+                "access_token": REDACTED,
+                "credentials": {"client_id": "id", "client_secret": REDACTED},
+                "items": [{"token": REDACTED, "name": "a"}, {"token": REDACTED, "name": "b"}]
+            })
+        );
+    }
+
+    #[test]
+    fn redact_with_replacement() {
+        let mut value = json!({"balance": 100, "name": "June"});
+        redact_with(
+            &mut value,
+            &[
+                Redaction {
+                    path: "balance".to_string(),
+                    replacement: json!(0),
+                },
+                Redaction::new("name"),
+            ],
+        );
+        assert_eq!(value, json!({"balance": 0, "name": REDACTED}));
+    }
+}
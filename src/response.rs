@@ -0,0 +1,81 @@
+use crate::{body, GoldrustError};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// A captured HTTP response, including the status and headers
+///
+/// Save with [`Goldrust::save_response`](crate::Goldrust::save_response)
+/// when the mock should reproduce more than the response body,
+/// such as the status code or pagination headers.
+///
+/// Responses without a body, such as `204 No Content`, have a `null` body.
+/// Bodies which are `null` or an empty string are equal, as both are served as an empty body.
+#[derive(Clone, Eq, Debug, Serialize, Deserialize)]
+pub struct GoldenResponse {
+    pub status: u16,
+    pub headers: BTreeMap<String, String>,
+    pub body: serde_json::Value,
+}
+
+impl PartialEq for GoldenResponse {
+    fn eq(&self, other: &Self) -> bool {
+        self.status == other.status
+            && self.headers == other.headers
+            && (self.body == other.body || (self.has_empty_body() && other.has_empty_body()))
+    }
+}
+
+impl GoldenResponse {
+    /// A response without a body, e.g. `GoldenResponse::empty(204)` for `204 No Content`
+    pub fn empty(status: u16) -> Self {
+        Self {
+            status,
+            headers: BTreeMap::new(),
+            body: serde_json::Value::Null,
+        }
+    }
+
+    /// Whether the response has no body
+    pub fn has_empty_body(&self) -> bool {
+        body::is_empty(&self.body)
+    }
+
+    /// The raw body
+    ///
+    /// Bodies stored base64 encoded, as `{"encoding": "base64", "data": "..."}`, are decoded,
+    /// strings are returned as is, and other bodies are serialized as JSON.
+    pub fn body_bytes(&self) -> Result<Vec<u8>, GoldrustError> {
+        body::to_bytes(&self.body)
+    }
+}
+
+#[cfg(feature = "reqwest")]
+impl GoldenResponse {
+    /// Capture the status, headers and body of a [`reqwest::Response`]
+    ///
+    /// JSON objects and arrays, and other values of a JSON `Content-Type`, are kept as JSON,
+    /// empty bodies are `null`, and other bodies are stored the same way as
+    /// [`Goldrust::save_body`](crate::Goldrust::save_body), so that they are replayed verbatim.
+    pub(crate) async fn from_reqwest(response: reqwest::Response) -> Result<Self, reqwest::Error> {
+        let status = response.status().as_u16();
+        let mut headers = BTreeMap::<String, String>::new();
+        for (name, value) in response.headers() {
+            let value = String::from_utf8_lossy(value.as_bytes());
+            headers
+                .entry(name.to_string())
+                .and_modify(|values| {
+                    values.push_str(", ");
+                    values.push_str(&value);
+                })
+                .or_insert_with(|| value.into_owned());
+        }
+        let bytes = response.bytes().await?;
+        let content_type = headers.get("content-type").map(String::as_str);
+        let body = body::from_response_bytes(&bytes, content_type);
+        Ok(Self {
+            status,
+            headers,
+            body,
+        })
+    }
+}
@@ -0,0 +1,132 @@
+use derive_more::Display;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// A retry policy for external API calls
+///
+/// Failed attempts are retried with an exponential backoff,
+/// waiting `base_delay`, `base_delay * 2`, `base_delay * 4`, ... between attempts.
+#[derive(
+    Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Debug, Default, Serialize, Deserialize, Display,
+)]
+#[display("{max_attempts}, {base_delay:?}")]
+pub struct RetryPolicy {
+    /// The maximum number of attempts, including the first one
+    pub max_attempts: u32,
+    /// The delay before the first retry
+    pub base_delay: Duration,
+}
+
+impl RetryPolicy {
+    /// The delay before the given retry, starting from `1`
+    pub fn delay(&self, retry: u32) -> Duration {
+        self.base_delay
+            .saturating_mul(2u32.saturating_pow(retry.saturating_sub(1)))
+    }
+}
+
+/// Run the closure, retrying on errors according to the policy
+///
+/// Without a policy, the closure runs exactly once.
+#[cfg(feature = "tokio")]
+pub(crate) async fn retry<F, Fut, T, E>(policy: Option<RetryPolicy>, mut f: F) -> Result<T, E>
+where
+    F: FnMut() -> Fut,
+    Fut: std::future::Future<Output = Result<T, E>>,
+    E: std::fmt::Debug,
+{
+    let max_attempts = policy.map_or(1, |policy| policy.max_attempts.max(1));
+    let mut attempt = 1;
+    loop {
+        match f().await {
+            Ok(t) => return Ok(t),
+            Err(e) if attempt < max_attempts => {
+                let delay = policy
+                    .map(|policy| policy.delay(attempt))
+                    .unwrap_or_default();
+                tracing::warn!(?e, attempt, ?delay, "External API call failed, retrying");
+                tokio::time::sleep(delay).await;
+                attempt += 1;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// Run the closure, retrying on errors according to the policy, without an async runtime
+///
+/// Same as [`retry`], except that the delay blocks the current thread.
+pub(crate) fn retry_blocking<F, T, E>(policy: Option<RetryPolicy>, mut f: F) -> Result<T, E>
+where
+    F: FnMut() -> Result<T, E>,
+    E: std::fmt::Debug,
+{
+    let max_attempts = policy.map_or(1, |policy| policy.max_attempts.max(1));
+    let mut attempt = 1;
+    loop {
+        match f() {
+            Ok(t) => return Ok(t),
+            Err(e) if attempt < max_attempts => {
+                let delay = policy
+                    .map(|policy| policy.delay(attempt))
+                    .unwrap_or_default();
+                tracing::warn!(?e, attempt, ?delay, "External API call failed, retrying");
+                std::thread::sleep(delay);
+                attempt += 1;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn delay_backs_off_exponentially() {
+        let policy = RetryPolicy {
+            max_attempts: 4,
+            base_delay: Duration::from_millis(100),
+        };
+        assert_eq!(policy.delay(1), Duration::from_millis(100));
+        assert_eq!(policy.delay(3), Duration::from_millis(400));
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn retry_until_success() {
+        let policy = RetryPolicy {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(1),
+        };
+        let mut attempts = 0;
+        let result: Result<u32, &str> = retry(Some(policy), || {
+            attempts += 1;
+            let attempt = attempts;
+            async move {
+                match attempt {
+                    3 => Ok(attempt),
+                    _ => Err("503"),
+                }
+            }
+        })
+        .await;
+        assert_eq!(result, Ok(3));
+    }
+
+    #[test]
+    fn retry_blocking_gives_up_after_max_attempts() {
+        let policy = RetryPolicy {
+            max_attempts: 2,
+            base_delay: Duration::from_millis(1),
+        };
+        let mut attempts = 0;
+        let result: Result<(), &str> = retry_blocking(Some(policy), || {
+            attempts += 1;
+            Err("503")
+        });
+        assert_eq!(result, Err("503"));
+        assert_eq!(attempts, 2);
+    }
+}
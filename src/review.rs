@@ -0,0 +1,140 @@
+//! Review of pending golden files
+//!
+//! With [`GoldrustBuilder::review`](crate::GoldrustBuilder::review),
+//! recording writes to pending golden files (e.g. `test-name.json.new`)
+//! instead of overwriting the golden files,
+//! which can then be accepted or rejected before being committed.
+
+use crate::{compare, GoldrustError};
+use std::ffi::OsStr;
+use std::io::{BufRead, Write};
+use std::path::{Path, PathBuf};
+
+/// The extension appended to pending golden files, without the leading dot
+pub(crate) const EXTENSION: &str = "new";
+
+/// The pending golden file for the golden file
+pub(crate) fn pending_path(path: &Path) -> PathBuf {
+    let mut file_name = path.file_name().unwrap_or_default().to_os_string();
+    file_name.push(".");
+    file_name.push(EXTENSION);
+    path.with_file_name(file_name)
+}
+
+/// Find pending golden files in the directory, sorted
+pub fn find_pending(dir: impl AsRef<Path>) -> Result<Vec<PathBuf>, GoldrustError> {
+    let mut pending = Vec::new();
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_file() && path.extension() == Some(OsStr::new(EXTENSION)) {
+            pending.push(path);
+        }
+    }
+    pending.sort();
+    Ok(pending)
+}
+
+/// Accept a pending golden file, replacing the golden file
+///
+/// Returns the path to the golden file.
+pub fn accept(pending: impl AsRef<Path>) -> Result<PathBuf, GoldrustError> {
+    let pending = pending.as_ref();
+    let golden = pending.with_extension("");
+    std::fs::rename(pending, &golden)?;
+    tracing::debug!(?golden, "Accepted pending golden file");
+    Ok(golden)
+}
+
+/// Reject a pending golden file, keeping the golden file as-is
+pub fn reject(pending: impl AsRef<Path>) -> Result<(), GoldrustError> {
+    let pending = pending.as_ref();
+    std::fs::remove_file(pending)?;
+    tracing::debug!(?pending, "Rejected pending golden file");
+    Ok(())
+}
+
+/// Review the pending golden files in the directory interactively
+///
+/// For each pending golden file, the differences to the golden file are shown,
+/// and the answer read from stdin decides whether to accept, reject or skip it.
+pub fn review(dir: impl AsRef<Path>) -> Result<(), GoldrustError> {
+    review_with(dir, std::io::stdin().lock(), std::io::stdout().lock())
+}
+
+fn review_with<R, W>(
+    dir: impl AsRef<Path>,
+    mut input: R,
+    mut output: W,
+) -> Result<(), GoldrustError>
+where
+    R: BufRead,
+    W: Write,
+{
+    for pending in find_pending(dir)? {
+        let golden = pending.with_extension("");
+        writeln!(output, "Pending golden file: {}", pending.display())?;
+        match std::fs::read(&golden) {
+            Ok(golden) => {
+                for difference in differences(&golden, &std::fs::read(&pending)?) {
+                    writeln!(output, "  {}", difference)?;
+                }
+            }
+            Err(_) => writeln!(output, "  (new golden file)")?,
+        }
+
+        write!(output, "[a]ccept, [r]eject, [s]kip? ")?;
+        output.flush()?;
+        let mut answer = String::new();
+        input.read_line(&mut answer)?;
+        match answer.trim() {
+            "a" | "accept" => {
+                accept(&pending)?;
+            }
+            "r" | "reject" => reject(&pending)?,
+            _ => writeln!(output, "Skipped")?,
+        }
+    }
+    Ok(())
+}
+
+/// The differences between the JSON contents,
+/// or a summary when they are not comparable as JSON
+fn differences(golden: &[u8], pending: &[u8]) -> Vec<String> {
+    match (
+        serde_json::from_slice(golden),
+        serde_json::from_slice(pending),
+    ) {
+        (Ok(golden), Ok(pending)) => compare::diff(&golden, &pending),
+        _ if golden == pending => Vec::new(),
+        _ => vec!["(content changed)".to_string()],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn review_accepts_and_rejects() {
+        let temp = crate::test_support::temp_dir();
+        let dir = temp.path();
+        std::fs::write(dir.join("accepted.json"), r#"{"age": 1}"#).unwrap();
+        std::fs::write(dir.join("accepted.json.new"), r#"{"age": 2}"#).unwrap();
+        std::fs::write(dir.join("rejected.json"), r#"{"age": 1}"#).unwrap();
+        std::fs::write(dir.join("rejected.json.new"), r#"{"age": 2}"#).unwrap();
+
+        let mut output = Vec::new();
+        review_with(dir, "a\nr\n".as_bytes(), &mut output).unwrap();
+
+        assert!(String::from_utf8(output).unwrap().contains("~ age: 1 -> 2"));
+        assert_eq!(
+            std::fs::read_to_string(dir.join("accepted.json")).unwrap(),
+            r#"{"age": 2}"#
+        );
+        assert_eq!(
+            std::fs::read_to_string(dir.join("rejected.json")).unwrap(),
+            r#"{"age": 1}"#
+        );
+        assert!(find_pending(dir).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,84 @@
+use crate::GoldrustError;
+use std::path::{Path, PathBuf};
+
+/// Find golden files in the directory which don't belong to any of the known names
+///
+/// Known names are golden file names without the extension,
+/// the same as the names passed to [`Goldrust::new`](crate::Goldrust::new).
+/// A file belongs to a name when it is the golden file of the name (e.g. `name.json`),
+/// or one of its named golden files (e.g. `name__first_call.json`).
+///
+/// Only files directly in the directory are checked, and the result is sorted.
+/// A directory which does not exist has no stale files.
+pub fn find_stale(
+    dir: impl AsRef<Path>,
+    known_names: &[String],
+) -> Result<Vec<PathBuf>, GoldrustError> {
+    let dir = dir.as_ref();
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut stale = Vec::new();
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        if !entry.file_type()?.is_file() {
+            continue;
+        }
+        let file_name = entry.file_name();
+        let file_name = file_name.to_string_lossy();
+        let known = known_names.iter().any(|name| {
+            file_name
+                .strip_prefix(name.as_str())
+                .is_some_and(|rest| rest.starts_with('.') || rest.starts_with("__"))
+        });
+        if !known {
+            stale.push(entry.path());
+        }
+    }
+    stale.sort();
+    Ok(stale)
+}
+
+/// Assert that the directory has no stale golden files
+///
+/// Use this in a dedicated test, which collects the names of all active tests,
+/// to keep the golden files clean. Check [`find_stale`] for details.
+///
+/// # Panics
+///
+/// Panics with the stale golden files,
+/// or when the directory can't be read.
+#[track_caller]
+pub fn assert_no_stale(dir: impl AsRef<Path>, known_names: &[String]) {
+    let stale = find_stale(dir, known_names)
+        .unwrap_or_else(|e| panic!("Failed to find stale golden files: {}", e));
+    if !stale.is_empty() {
+        panic!("Found stale golden files: {:#?}", stale);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn find_stale_golden_files() {
+        let temp = crate::test_support::temp_dir();
+        let dir = temp.path();
+        for file_name in [
+            "active.json",
+            "active__first_call.json",
+            "active_other.json",
+            "deleted.json",
+        ] {
+            std::fs::write(dir.join(file_name), "{}").unwrap();
+        }
+
+        let stale = find_stale(dir, &["active".to_string()]).unwrap();
+        assert_eq!(
+            stale,
+            vec![dir.join("active_other.json"), dir.join("deleted.json")]
+        );
+    }
+}
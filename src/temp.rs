@@ -0,0 +1,106 @@
+//! Cleanup of temporary files left behind by interrupted writes
+
+use crate::GoldrustError;
+use std::cmp::Ordering;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// A shared temporary directory, removed when the last clone is dropped
+///
+/// Directories are compared by their path,
+/// so that [`Goldrust`](crate::Goldrust) keeps implementing the common traits.
+#[derive(Clone, Debug)]
+pub(crate) struct EphemeralDir(pub(crate) Arc<tempfile::TempDir>);
+
+impl PartialEq for EphemeralDir {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.path() == other.0.path()
+    }
+}
+
+impl Eq for EphemeralDir {}
+
+impl PartialOrd for EphemeralDir {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for EphemeralDir {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.path().cmp(other.0.path())
+    }
+}
+
+/// Remove stale temporary files in the directory, returning the removed files, sorted
+///
+/// Golden files are written to a temporary file (e.g. `.test-name.json.1234.tmp`),
+/// which is then renamed to the golden file,
+/// so temporary files are only left behind when writing was interrupted,
+/// e.g. when the test process was killed.
+/// Temporary files of the current process are kept, as they might still be written.
+///
+/// Pending golden files are kept as well, as they are waiting for [`review`](crate::review).
+/// Only files directly in the directory are checked.
+/// A directory which does not exist has no temporary files.
+pub fn clean_temp(dir: impl AsRef<Path>) -> Result<Vec<PathBuf>, GoldrustError> {
+    let dir = dir.as_ref();
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut removed = Vec::new();
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        if !entry.file_type()?.is_file() {
+            continue;
+        }
+        let stale = temp_file_pid(&entry.file_name().to_string_lossy())
+            .is_some_and(|pid| pid != std::process::id());
+        if stale {
+            std::fs::remove_file(entry.path())?;
+            tracing::debug!(path = ?entry.path(), "Removed stale temporary file");
+            removed.push(entry.path());
+        }
+    }
+    removed.sort();
+    Ok(removed)
+}
+
+/// The id of the process which wrote the temporary file, if the file name is one
+///
+/// Temporary files are named `.{file name}.{pid}.tmp`.
+fn temp_file_pid(file_name: &str) -> Option<u32> {
+    let rest = file_name.strip_prefix('.')?.strip_suffix(".tmp")?;
+    let (name, pid) = rest.rsplit_once('.')?;
+    if name.is_empty() {
+        return None;
+    }
+    pid.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clean_temp_removes_stale_temp_files() {
+        let temp = crate::test_support::temp_dir();
+        let dir = temp.path();
+        let own = format!(".golden.json.{}.tmp", std::process::id());
+        for file_name in [
+            ".golden.json.0.tmp",
+            own.as_str(),
+            "golden.json",
+            "golden.json.new",
+            ".gitignore",
+        ] {
+            std::fs::write(dir.join(file_name), "{}").unwrap();
+        }
+
+        let removed = clean_temp(dir).unwrap();
+        assert_eq!(removed, vec![dir.join(".golden.json.0.tmp")]);
+        assert!(dir.join(own).exists());
+        assert!(dir.join("golden.json.new").exists());
+    }
+}
@@ -0,0 +1,42 @@
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+/// Substitute `{{name}}` placeholders in the strings of the value, recursively
+///
+/// Placeholders without a registered variable are kept as is.
+pub(crate) fn substitute(value: &mut Value, vars: &BTreeMap<String, String>) {
+    match value {
+        Value::String(text) if text.contains("{{") => {
+            for (name, var) in vars {
+                *text = text.replace(&format!("{{{{{}}}}}", name), var);
+            }
+        }
+        Value::Array(values) => values.iter_mut().for_each(|value| substitute(value, vars)),
+        Value::Object(map) => map.values_mut().for_each(|value| substitute(value, vars)),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn substitute_placeholders() {
+        let vars = BTreeMap::from([("goldrust_now".to_string(), "2024-01-01".to_string())]);
+        let mut value = serde_json::json!({
+            "now": "{{goldrust_now}}",
+            "items": [{"message": "Today is {{goldrust_now}}"}],
+            "unknown": "{{other}}",
+        });
+        substitute(&mut value, &vars);
+        assert_eq!(
+            value,
+            serde_json::json!({
+                "now": "2024-01-01",
+                "items": [{"message": "Today is 2024-01-01"}],
+                "unknown": "{{other}}",
+            })
+        );
+    }
+}
@@ -0,0 +1,154 @@
+use sha2::{Digest, Sha256};
+
+/// The test id of a test function, which is used as the golden file name
+///
+/// The transformation is:
+/// 1. Join the module path and the function name with `::`
+///    (e.g. `my_crate::tests` and `get_user` → `my_crate::tests::get_user`)
+/// 2. Remove the `{{closure}}` segments,
+///    which are added for async tests and closures (e.g. with `#[tokio::test]`)
+/// 3. Replace `::` with `-` (e.g. `my_crate-tests-get_user`)
+///
+/// As Rust identifiers can't contain `-`,
+/// distinct test functions always get distinct ids.
+/// [`goldrust!`](crate::goldrust!) generates this id for the enclosing function,
+/// followed by `-` and a hash of the call site, so that several calls in one function
+/// get distinct golden files (e.g. `my_crate-tests-get_user-1a2b3c4d`).
+/// The hash is the first 8 hex digits of the SHA-256 digest of `{line}:{column}` of the call,
+/// so it changes when the call moves, but never depends on the order calls are reached.
+/// Use `goldrust!(name = ...)` for golden file names which don't depend on the call site.
+pub fn test_id_for(module_path: &str, fn_name: &str) -> String {
+    module_path
+        .split("::")
+        .chain(fn_name.split("::"))
+        .filter(|segment| !segment.is_empty() && *segment != "{{closure}}")
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+/// The test id of a test function in the package, as generated by [`goldrust!`](crate::goldrust!)
+///
+/// When `GOLDRUST_CRATE_NAME_PREFIX` is `true`, the test id is prefixed with the package name,
+/// so tests of different crates sharing a `GOLDRUST_DIR` don't collide.
+/// Test ids which already start with the crate name, such as those of unit tests, are kept as is.
+///
+/// This is an implementation detail of [`goldrust!`](crate::goldrust!).
+///
+/// # Panics
+///
+/// Panics when `GOLDRUST_CRATE_NAME_PREFIX` is not parseable as a boolean.
+#[doc(hidden)]
+pub fn __package_test_id(package_name: &str, module_path: &str, fn_name: &str) -> String {
+    let test_id = test_id_for(module_path, fn_name);
+    let prefix =
+        crate::builder::env_bool("GOLDRUST_CRATE_NAME_PREFIX").unwrap_or_else(|e| panic!("{}", e));
+    if prefix {
+        with_crate_name(package_name, module_path, test_id)
+    } else {
+        test_id
+    }
+}
+
+/// Suffix the test id with the hash of the call site, given as `(line, column)`
+///
+/// This is an implementation detail of [`goldrust!`](crate::goldrust!).
+#[doc(hidden)]
+pub fn __call_site_test_id(test_id: String, call_site: (u32, u32)) -> String {
+    format!("{}-{}", test_id, call_site_hash(call_site))
+}
+
+/// The first 8 hex digits of the SHA-256 digest of `{line}:{column}`
+fn call_site_hash((line, column): (u32, u32)) -> String {
+    Sha256::digest(format!("{}:{}", line, column))[..4]
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+/// Prefix the test id with the package name, unless the module path already starts with the crate
+fn with_crate_name(package_name: &str, module_path: &str, test_id: String) -> String {
+    let crate_name = package_name.replace('-', "_");
+    if module_path.split("::").next() == Some(crate_name.as_str()) {
+        test_id
+    } else {
+        format!("{}-{}", package_name, test_id)
+    }
+}
+
+/// The test id of the enclosing function
+///
+/// This is an implementation detail of [`goldrust!`](crate::goldrust!).
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __test_id {
+    () => {{
+        fn f() {}
+        fn type_name_of_val<T>(_: T) -> &'static str {
+            std::any::type_name::<T>()
+        }
+        let path = type_name_of_val(f).strip_suffix("::f").unwrap_or("");
+        let fn_name = path
+            .strip_prefix(module_path!())
+            .and_then(|rest| rest.strip_prefix("::"))
+            .unwrap_or(path);
+        $crate::__call_site_test_id(
+            $crate::__package_test_id(env!("CARGO_PKG_NAME"), module_path!(), fn_name),
+            (line!(), column!()),
+        )
+    }};
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_id_strips_closures() {
+        assert_eq!(
+            test_id_for("my_crate::tests", "get_user::{{closure}}::{{closure}}"),
+            "my_crate-tests-get_user"
+        );
+    }
+
+    #[test]
+    fn test_id_with_crate_name() {
+        assert_eq!(
+            with_crate_name("my-crate", "api", "api-get_user".to_string()),
+            "my-crate-api-get_user"
+        );
+        assert_eq!(
+            with_crate_name(
+                "my-crate",
+                "my_crate::tests",
+                "my_crate-tests-get_user".to_string()
+            ),
+            "my_crate-tests-get_user"
+        );
+    }
+
+    #[test]
+    fn test_id_of_enclosing_function() {
+        let test_id = __test_id!();
+        let (test_id, hash) = test_id.rsplit_once('-').unwrap();
+        assert_eq!(
+            test_id,
+            "goldrust-test_id-tests-test_id_of_enclosing_function"
+        );
+        assert_eq!(hash.len(), 8);
+    }
+
+    #[test]
+    fn call_site_hash_is_stable() {
+        assert_eq!(call_site_hash((12, 5)), "536ee2e1");
+    }
+
+    #[test]
+    fn test_ids_of_call_sites_in_one_function_are_distinct() {
+        let first = __test_id!();
+        let second = __test_id!();
+        assert_ne!(first, second);
+
+        let repeated = (0..2).map(|_| __test_id!()).collect::<Vec<_>>();
+        assert_eq!(repeated[0], repeated[1]);
+    }
+}
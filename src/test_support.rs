@@ -0,0 +1,29 @@
+//! Helpers shared by the unit tests
+
+use crate::{temp, Goldrust};
+use std::sync::Arc;
+
+/// A unique temporary directory for a single test, which is removed when dropped
+pub(crate) fn temp_dir() -> tempfile::TempDir {
+    tempfile::Builder::new()
+        .prefix("goldrust-")
+        .tempdir()
+        .expect("Failed to create a temporary directory")
+}
+
+/// Goldrust saving to the golden file in a unique temporary directory
+///
+/// External API calls are allowed and golden files are updated.
+/// The directory is removed when Goldrust is dropped.
+pub(crate) fn recording(golden_file_name: &str) -> Goldrust {
+    let dir = temp_dir();
+    let mut goldrust = Goldrust::builder()
+        .dir(dir.path().to_path_buf())
+        .allow_external_api_call(true)
+        .update_golden_files(true)
+        .golden_file_name(golden_file_name.to_string())
+        .build()
+        .expect("Failed to build");
+    goldrust.ephemeral_dir = Some(temp::EphemeralDir(Arc::new(dir)));
+    goldrust
+}
@@ -0,0 +1,169 @@
+//! Recording many golden files in one external pass
+
+use crate::{Goldrust, GoldrustError};
+use std::future::Future;
+use std::path::PathBuf;
+use std::pin::Pin;
+
+/// The content of a golden file, produced by an external call
+type Task<T> = Pin<Box<dyn Future<Output = Result<T, GoldrustError>> + Send>>;
+
+/// A batch of golden files, recorded together with a concurrency limit
+///
+/// This is useful when bootstrapping, instead of running each test to record its golden file.
+/// Each task produces the content of a golden file,
+/// usually by calling the external api with a shared client,
+/// and the content is saved with [`Goldrust::save`].
+///
+/// ```no_run
+/// # async fn example() {
+/// let client = reqwest::Client::new();
+/// let results = goldrust::WarmUp::new("tests/resources/golden")
+///     .concurrency(2)
+///     .add("users-get_user", {
+///         let client = client.clone();
+///         async move {
+///             let response = client.get("https://api.example.com/users/1").send().await.unwrap();
+///             Ok(response.json::<serde_json::Value>().await.unwrap())
+///         }
+///     })
+///     .run()
+///     .await;
+/// # }
+/// ```
+///
+/// Requires the `tokio` feature.
+pub struct WarmUp<T> {
+    dir: PathBuf,
+    concurrency: Option<usize>,
+    tasks: Vec<(String, Task<T>)>,
+}
+
+impl<T> WarmUp<T>
+where
+    T: serde::Serialize + serde::de::DeserializeOwned + std::fmt::Debug + Send + 'static,
+{
+    /// Create an empty batch, saving golden files in `dir`
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            concurrency: None,
+            tasks: Vec::new(),
+        }
+    }
+
+    /// The maximum number of tasks running at the same time, which defaults to no limit
+    ///
+    /// # Panics
+    ///
+    /// Panics when `concurrency` is `0`.
+    pub fn concurrency(mut self, concurrency: usize) -> Self {
+        assert!(concurrency > 0, "The concurrency must be at least 1");
+        self.concurrency = Some(concurrency);
+        self
+    }
+
+    /// Add a task producing the content of the golden file `name`
+    ///
+    /// The name is the golden file name, the same as passed to [`Goldrust::new`].
+    pub fn add<F>(mut self, name: impl Into<String>, task: F) -> Self
+    where
+        F: Future<Output = Result<T, GoldrustError>> + Send + 'static,
+    {
+        self.tasks.push((name.into(), Box::pin(task)));
+        self
+    }
+
+    /// Run the tasks, saving the golden file of each
+    ///
+    /// Returns the result of each task in the order they were added,
+    /// with the path of the saved golden file,
+    /// so a failing task doesn't prevent recording the others.
+    pub async fn run(self) -> Vec<(String, Result<PathBuf, GoldrustError>)> {
+        let limit = self.concurrency.unwrap_or(usize::MAX);
+        let mut running = tokio::task::JoinSet::new();
+        let mut results = Vec::with_capacity(self.tasks.len());
+        for (index, (name, task)) in self.tasks.into_iter().enumerate() {
+            if running.len() >= limit {
+                results.extend(join_next(&mut running).await);
+            }
+            let dir = self.dir.clone();
+            running.spawn(async move {
+                let saved = record(dir, &name, task).await;
+                (index, name, saved)
+            });
+        }
+        while let Some(result) = join_next(&mut running).await {
+            results.push(result);
+        }
+        results.sort_by_key(|(index, _, _)| *index);
+        results
+            .into_iter()
+            .map(|(_, name, saved)| (name, saved))
+            .collect()
+    }
+}
+
+/// The result of the task which finishes next, resuming its panic if it panicked
+async fn join_next<R: 'static>(running: &mut tokio::task::JoinSet<R>) -> Option<R> {
+    match running.join_next().await? {
+        Ok(result) => Some(result),
+        Err(e) if e.is_panic() => std::panic::resume_unwind(e.into_panic()),
+        Err(e) => panic!("Warm up task was cancelled: {}", e),
+    }
+}
+
+/// Run the task, and save its content to the golden file `name`
+async fn record<T>(dir: PathBuf, name: &str, task: Task<T>) -> Result<PathBuf, GoldrustError>
+where
+    T: serde::Serialize + serde::de::DeserializeOwned + std::fmt::Debug,
+{
+    let mut goldrust = Goldrust::builder()
+        .dir(dir)
+        .allow_external_api_call(true)
+        .update_golden_files(true)
+        .golden_file_name(name.to_string())
+        .build()?;
+    let content = match task.await {
+        Ok(content) => content,
+        Err(e) => {
+            goldrust.disarm();
+            return Err(e);
+        }
+    };
+    goldrust.save(content)?;
+    Ok(goldrust.golden_file_path.to_path_buf())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn warm_up_saves_each_golden_file() {
+        let temp = crate::test_support::temp_dir();
+        let dir = temp.path();
+
+        let results = WarmUp::new(dir)
+            .concurrency(1)
+            .add("first", async { Ok(serde_json::json!({"page": 1})) })
+            .add("failing", async {
+                Err(GoldrustError::InvalidConfig {
+                    reason: "Failed".to_string(),
+                })
+            })
+            .add("second", async { Ok(serde_json::json!({"page": 2})) })
+            .run()
+            .await;
+
+        let names: Vec<&str> = results.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(names, ["first", "failing", "second"]);
+        assert!(results[1].1.is_err());
+        let second = results[2].1.as_ref().expect("Failed to save");
+        assert_eq!(second, &dir.join("second.json"));
+        assert_eq!(
+            serde_json::from_slice::<serde_json::Value>(&std::fs::read(second).unwrap()).unwrap(),
+            serde_json::json!({"page": 2})
+        );
+    }
+}
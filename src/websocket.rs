@@ -0,0 +1,75 @@
+use crate::GoldrustError;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+
+/// The direction of a WebSocket frame, from the point of view of the client
+#[derive(Clone, Copy, Eq, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WsDirection {
+    Sent,
+    Received,
+}
+
+/// The opcode of a WebSocket data frame
+#[derive(Clone, Copy, Eq, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WsOpcode {
+    Text,
+    Binary,
+}
+
+/// A WebSocket frame of a transcript
+///
+/// Save the ordered frames with [`Goldrust::save_transcript`](crate::Goldrust::save_transcript).
+/// Binary payloads are stored base64 encoded, to keep the transcript in JSON.
+#[derive(Clone, Eq, PartialEq, Debug, Serialize, Deserialize)]
+pub struct WsFrame {
+    pub direction: WsDirection,
+    pub opcode: WsOpcode,
+    pub payload: String,
+}
+
+impl WsFrame {
+    /// A text frame
+    pub fn text(direction: WsDirection, text: impl Into<String>) -> Self {
+        Self {
+            direction,
+            opcode: WsOpcode::Text,
+            payload: text.into(),
+        }
+    }
+
+    /// A binary frame
+    pub fn binary(direction: WsDirection, bytes: &[u8]) -> Self {
+        Self {
+            direction,
+            opcode: WsOpcode::Binary,
+            payload: base64::engine::general_purpose::STANDARD.encode(bytes),
+        }
+    }
+
+    /// The raw payload, decoding binary payloads
+    pub fn payload_bytes(&self) -> Result<Vec<u8>, GoldrustError> {
+        match self.opcode {
+            WsOpcode::Text => Ok(self.payload.clone().into_bytes()),
+            WsOpcode::Binary => {
+                Ok(base64::engine::general_purpose::STANDARD.decode(&self.payload)?)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn binary_payload_round_trip() {
+        let frame = WsFrame::binary(WsDirection::Received, &[0xff, 0x00]);
+        assert_eq!(
+            serde_json::to_value(&frame).unwrap(),
+            serde_json::json!({"direction": "received", "opcode": "binary", "payload": "/wA="})
+        );
+        assert_eq!(frame.payload_bytes().unwrap(), vec![0xff, 0x00]);
+    }
+}
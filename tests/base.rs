@@ -19,10 +19,11 @@ async fn base() {
     let mock_server = MockServer::start().await;
 
     // ⭐️ Set the domain to the mock server uri
-    let domain = match goldrust.response_source {
-        ResponseSource::Local => mock_server.uri(),
-        ResponseSource::External => "https://some-external-api.com".to_string(),
-    };
+    goldrust.with_base_urls(mock_server.uri(), "https://some-external-api.com");
+    let domain = goldrust
+        .base_url()
+        .expect("Base url should be set")
+        .to_string();
 
     let url_path = "/api/actual";
 
@@ -57,25 +58,13 @@ async fn base() {
     let response_body: Data = response.json().await.expect("Failed to get bytes");
     tracing::debug!(?response_body, "Response body");
 
-    let golden_file_text =
-        std::fs::read_to_string(&goldrust.golden_file_path).expect("Failed to read golden file");
-
     // The response body should match the golden file
-    assert_eq!(
-        serde_json::to_string(&response_body).expect("Failed to serialize"),
-        normalize_json(&golden_file_text)
-    );
+    goldrust.verify(&response_body);
 
     // ⭐️ Using the closure to save content to the golden file
     goldrust.save(response_body).expect("Failed to save");
 }
 
-fn normalize_json(json: &str) -> String {
-    json.replace("\r\n", "\n")
-        .replace(" ", "")
-        .replace("\n", "")
-}
-
 #[tracing::instrument]
 fn create_response_template<P: AsRef<Path> + std::fmt::Debug>(path: P) -> ResponseTemplate {
     let path = path.as_ref();